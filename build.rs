@@ -0,0 +1,64 @@
+//! Compresses every file in `data` into the build directory and generates the table of
+//! embedded assets that `src/assets.rs` includes.
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const DATA_DIR: &str = "data";
+const COMPRESSION_LEVEL: u8 = 6;
+
+fn main() -> io::Result<()> {
+    println!("cargo::rerun-if-changed={DATA_DIR}");
+
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").expect("OUT_DIR is set by cargo"));
+    let assets_dir = out_dir.join("assets");
+    fs::create_dir_all(&assets_dir)?;
+
+    let mut files = Vec::new();
+    collect(Path::new(DATA_DIR), &mut files)?;
+    // Sorted by key, so that assets can be found with a binary search.
+    let mut keys: Vec<(String, PathBuf)> =
+        files.into_iter().map(|path| (key(&path), path)).collect();
+    keys.sort();
+
+    let mut table = String::from("&[\n");
+    for (i, (key, path)) in keys.iter().enumerate() {
+        let compressed = miniz_oxide::deflate::compress_to_vec(&fs::read(path)?, COMPRESSION_LEVEL);
+        let file = assets_dir.join(format!("{i}.deflate"));
+        fs::write(&file, compressed)?;
+        table.push_str(&format!(
+            "    ({key:?}, include_bytes!({:?})),\n",
+            file.display()
+        ));
+    }
+    table.push(']');
+
+    fs::write(out_dir.join("assets.rs"), table)
+}
+
+fn collect(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// The path relative to `data` with `/` between the components, like `themes/dots.json`.
+fn key(path: &Path) -> String {
+    let relative = path
+        .strip_prefix(DATA_DIR)
+        .expect("files are collected from the data dir");
+    let components: Vec<_> = relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect();
+    components.join("/")
+}
@@ -1,15 +1,19 @@
 #![allow(dead_code, clippy::new_without_default, clippy::single_match)]
 
 pub mod quick_menu;
+pub mod stats_view;
 pub mod tab;
 
-use quick_menu::QuickMenu;
+use quick_menu::{QuickMenu, QuickMenuAction};
+use stats_view::{StatsAction, StatsView};
 use tab::Tab;
 
-use crate::Config;
-use crate::monkeytype::{Language, QuoteLanguage};
-use crate::typing::{Mode, QuoteLength, Seconds, TestState, WordCount};
+use crate::user::config::ConfigWatcher;
+use crate::{Config, ConfigOverrides, Theme};
+use crate::monkeytype::{Challenge, Language, QuoteLanguage};
+use crate::typing::{KeystrokeLog, Mode, TestState, TextOrder};
 use crate::user::Stats;
+use crate::user::stats::{ResultStatus, TestResult};
 
 use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
 use ratatui::{
@@ -18,48 +22,162 @@ use ratatui::{
     widgets::{Block, BorderType, Widget},
 };
 
+use std::time::Duration;
+
 const TITLE: &str = "Type";
+const TICK_RATE: Duration = Duration::from_millis(50);
+/// Older notices are dropped when there are more.
+const MAX_NOTICES: usize = 5;
 
 pub struct App {
     exit: bool,
     current_tab: Tab,
     test_state: TestState,
     config: Config,
+    /// Settings from the command line and the ones of the saved config they replaced.
+    overrides: ConfigOverrides,
+    replaced: ConfigOverrides,
+    watcher: ConfigWatcher,
+    /// Whether a reloaded config still has to be applied to the tests.
+    config_pending: bool,
+    /// Problems with the config, the data files and the stats, shown at the bottom.
+    notices: Vec<String>,
+    /// Problems found by the last reload of the config, replaced by the next one.
+    reload_notices: Vec<String>,
     stats: Stats,
+    stats_view: StatsView,
     quick_menu: QuickMenu,
 }
 
 impl App {
-    pub fn new() -> crate::Result<Self> {
-        Ok(App {
+    pub fn new() -> Self {
+        Self::with_overrides(ConfigOverrides::default())
+    }
+
+    /// Starts with `overrides` applied on top of the saved config. Problems with the config, the
+    /// data files or the stats are shown as notices, and the defaults are used instead.
+    pub fn with_overrides(overrides: ConfigOverrides) -> Self {
+        let mut notices = Vec::new();
+        let mut stats = Stats::load().unwrap_or_else(|err| {
+            notices.push(format!("Stats not loaded: {err}"));
+            Stats::empty(Stats::dir())
+        });
+        notices.extend(stats.take_errors().iter().map(ToString::to_string));
+        let (mut config, config_error) = Config::load_or_default();
+        notices.extend(config_error.iter().map(ToString::to_string));
+        let mut replaced = overrides.apply(&mut config);
+        let mut test_state = TestState::new();
+        if let Err(err) = test_state.apply_config(&config) {
+            // E.g. a sound set or a word list of the user that can't be loaded.
+            notices.push(err.to_string());
+            config = Config::default();
+            replaced = overrides.apply(&mut config);
+            if let Err(err) = test_state.apply_config(&config) {
+                notices.push(err.to_string());
+            }
+        }
+        if let Some(words) = &overrides.text {
+            test_state.set_mode(Mode::custom(words.clone(), TextOrder::Repeat, words.len()));
+            if let Err(err) = test_state.new_test(&config) {
+                notices.push(err.to_string());
+            }
+        }
+        let mut quick_menu = QuickMenu::new(&config, stats.tags());
+        notices.extend(quick_menu.take_errors().iter().map(ToString::to_string));
+
+        let mut app = App {
             exit: false,
             current_tab: Tab::Typing,
-            // test_state: TestState::new()?.mode(Mode::Quote(vec![QuoteLength::Short])),
-            test_state: TestState::new()?.mode(Mode::words(WordCount::W10)),
-            // test_state: TestState::new()?.mode(Mode::words(WordCount::W50).punctuation().numbers()),
-            config: Config::load()?,
-            stats: Stats::load(),
-            quick_menu: QuickMenu::new(),
-        })
+            test_state,
+            stats_view: StatsView::new(),
+            quick_menu,
+            config,
+            overrides,
+            replaced,
+            watcher: ConfigWatcher::new(),
+            config_pending: false,
+            notices: Vec::new(),
+            reload_notices: Vec::new(),
+            stats,
+        };
+        notices.into_iter().for_each(|notice| app.notify(notice));
+        app
     }
 
+    /// Runs until the user quits. Errors of the data files, the config and the stats are shown
+    /// as notices, only errors of the terminal end the app.
     pub fn run(&mut self, terminal: &mut DefaultTerminal) -> crate::Result<()> {
         while !self.exit {
             terminal.draw(|frame| self.draw(frame))?;
-            match event::read()? {
-                Event::Key(key_event) => self.handle_key_event(key_event)?,
-                _ => (),
+            if event::poll(TICK_RATE)? {
+                match event::read()? {
+                    Event::Key(key_event) => {
+                        if let Err(err) = self.handle_key_event(key_event) {
+                            self.notify(err);
+                        }
+                    }
+                    _ => (),
+                }
             }
+            self.test_state.tick();
+            self.save_results();
+            self.reload_config();
         }
         Ok(())
     }
 
+    /// Shows `notice` at the bottom, below the older ones.
+    fn notify(&mut self, notice: impl ToString) {
+        self.notices.push(notice.to_string());
+        let excess = self.notices.len().saturating_sub(MAX_NOTICES);
+        self.notices.drain(..excess);
+    }
+
     fn handle_key_event(&mut self, key_event: event::KeyEvent) -> crate::Result<()> {
         if self.quick_menu.is_visible() {
-            self.quick_menu.handle_key_event(key_event, self.config)?;
+            let previous = self.config.clone();
+            let action = self.quick_menu.handle_key_event(
+                key_event,
+                &mut self.config,
+                &mut self.stats,
+            )?;
+            match action {
+                Some(QuickMenuAction::StartChallenge(name)) => {
+                    let challenge = Challenge::load(&name)?;
+                    self.test_state.start_challenge(challenge, &self.config)?;
+                    self.current_tab = Tab::Typing;
+                }
+                Some(QuickMenuAction::StopChallenge) => {
+                    self.test_state.stop_challenge(&self.config)?;
+                }
+                Some(QuickMenuAction::ConfigChanged) => {
+                    // A setting that can't be used, like a broken sound set, is taken back.
+                    if let Err(err) = self.test_state.apply_config(&self.config) {
+                        self.config = previous;
+                        self.quick_menu.refresh_settings(&self.config);
+                        return Err(err);
+                    }
+                    let saved = self.overrides.unapply(&self.replaced, &self.config).save();
+                    if let Err(err) = saved {
+                        self.notify(format!("Config not saved: {err}"));
+                    }
+                    self.watcher.mark_seen();
+                }
+                None => (),
+            }
         } else {
             match self.current_tab {
-                Tab::Typing => self.test_state.handle_key_event(key_event)?,
+                Tab::Typing => self.test_state.handle_key_event(key_event, &self.config)?,
+                Tab::Stats => match self.stats_view.handle_key_event(key_event, &self.stats) {
+                    Some(StatsAction::Replay(timestamp)) => {
+                        // Aborted tests don't have a replay.
+                        if let Ok(log) = KeystrokeLog::load(timestamp) {
+                            self.test_state.replay(log);
+                            self.current_tab = Tab::Typing;
+                        }
+                    }
+                    None => (),
+                },
                 _ => (),
             }
         }
@@ -70,7 +188,8 @@ impl App {
                 (KeyCode::Char('1'), KeyModifiers::CONTROL) => self.set_tab_from_num(0),
                 (KeyCode::Char('2'), KeyModifiers::CONTROL) => self.set_tab_from_num(1),
                 (KeyCode::Char('3'), KeyModifiers::CONTROL) => self.set_tab_from_num(2),
-                (KeyCode::Char('?'), KeyModifiers::CONTROL) => self.set_tab_from_num(3),
+                (KeyCode::Char('4'), KeyModifiers::CONTROL) => self.set_tab_from_num(3),
+                (KeyCode::Char('?'), KeyModifiers::CONTROL) => self.set_tab_from_num(4),
                 (KeyCode::Esc, KeyModifiers::NONE) => self.quick_menu.toggle(),
                 _ => (),
             },
@@ -80,6 +199,82 @@ impl App {
         Ok(())
     }
 
+    /// Stores the results of finished and aborted tests. The history comes first, replays,
+    /// achievements and key statistics are written even if something before them failed.
+    fn save_results(&mut self) {
+        let completed_challenges = self.test_state.take_completed_challenges();
+        for mut result in self.test_state.take_results() {
+            result.tags = self.stats.tags().active();
+            let status = result.status;
+            let achievements: Vec<(&String, TestResult)> = completed_challenges
+                .iter()
+                .filter(|(_, timestamp)| *timestamp == result.timestamp)
+                .map(|(challenge, _)| (challenge, result.clone()))
+                .collect();
+            match self.stats.add(result) {
+                Ok(Some(comparison)) => self.test_state.set_personal_best(comparison),
+                Ok(None) => (),
+                Err(err) => self.notify(format!("Result not saved: {err}")),
+            }
+            if status != ResultStatus::Aborted
+                && let Err(err) = self.test_state.keystrokes().save()
+            {
+                self.notify(format!("Replay not saved: {err}"));
+            }
+            for (challenge, result) in achievements {
+                if let Err(err) = self.stats.add_achievement(challenge, result) {
+                    self.notify(format!("Achievement not saved: {err}"));
+                }
+            }
+        }
+        let key_stats = self.test_state.take_key_stats();
+        if !key_stats.is_empty()
+            && let Err(err) = self.stats.add_key_stats(&key_stats)
+        {
+            self.notify(format!("Key statistics not saved: {err}"));
+        }
+    }
+
+    /// Applies the config and the user's themes when they were changed outside of the app. The
+    /// style changes at once, test settings once no test is in progress. When the config can't
+    /// be loaded, or its sounds or funboxes can't, the last good one is kept and the error is
+    /// shown.
+    fn reload_config(&mut self) {
+        if self.watcher.changed() {
+            let loaded = Config::load().and_then(|mut config| {
+                if let Some(theme) = &mut self.overrides.theme {
+                    *theme = Theme::load(theme.name.clone())?;
+                }
+                let replaced = self.overrides.apply(&mut config);
+                TestState::check_config(&config)?;
+                Ok((config, replaced))
+            });
+            match loaded {
+                Ok((config, replaced)) => {
+                    self.replaced = replaced;
+                    self.config_pending |= config.test_settings_differ(&self.config);
+                    self.config = config;
+                    self.reload_notices.clear();
+                    if !self.quick_menu.is_visible() {
+                        self.quick_menu = QuickMenu::new(&self.config, self.stats.tags());
+                        let errors = self.quick_menu.take_errors();
+                        self.reload_notices = errors.iter().map(ToString::to_string).collect();
+                    }
+                }
+                Err(err) => self.reload_notices = vec![format!("Config not reloaded: {err}")],
+            }
+        }
+
+        if self.config_pending && !self.test_state.is_in_progress() {
+            self.config_pending = false;
+            if self.test_state.is_finished() {
+                self.test_state.set_config(&self.config);
+            } else if let Err(err) = self.test_state.apply_config(&self.config) {
+                self.reload_notices.push(format!("Test settings not applied: {err}"));
+            }
+        }
+    }
+
     fn set_tab_from_num(&mut self, num: usize) {
         if let Some(tab) = Tab::from_number(num) {
             self.current_tab = tab;
@@ -110,7 +305,7 @@ impl App {
             .render(heading[0], buf);
 
         {
-            let tabs = [Tab::Typing, Tab::Settings];
+            let tabs = Tab::HEADING;
 
             let constraints = tabs
                 .iter()
@@ -118,7 +313,7 @@ impl App {
 
             let tab_layouts = Layout::horizontal(constraints)
                 .flex(layout::Flex::End)
-                .areas::<2>(heading[1])
+                .areas::<3>(heading[1])
                 .to_vec();
 
             for (tab, layout) in tabs.iter().zip(tab_layouts) {
@@ -164,15 +359,33 @@ impl App {
 
                 // Body
                 {
-                    self.test_state.render(&self.config.style, body, buf);
+                    self.test_state.render(&self.config, body, buf);
                 }
 
                 // Bottom
-                {}
+                if self.config.keymap {
+                    self.test_state.render_keymap(&self.config, bottom, buf);
+                }
+            }
+            Tab::Stats => {
+                self.stats_view
+                    .render(&self.stats, &self.config, body, buf);
             }
             _ => (),
         }
 
+        let notices = self.notices.iter().chain(&self.reload_notices);
+        let lines: Vec<Line> = notices.map(|notice| Line::raw(notice.as_str())).collect();
+        if !lines.is_empty() {
+            let height = lines.len() as u16;
+            let [_, bottom] = Layout::vertical([Constraint::Fill(1), Constraint::Length(height)])
+                .horizontal_margin(1)
+                .areas(area);
+            Text::from(lines)
+                .style(Style::new().fg(self.config.style.theme.error))
+                .render(bottom, buf);
+        }
+
         self.quick_menu.render(&self.config.style, area, buf);
     }
 }
@@ -1,8 +1,14 @@
 pub mod item;
 
-pub use item::QuickMenuItem;
+pub use item::{QuickMenuItem, Setting, Toggle};
 
-use crate::{Theme, Config};
+use crate::audio;
+use crate::monkeytype::{Challenge, KeyboardLayout, Language};
+use crate::typing::{Difficulty, QuoteLength, funbox};
+use crate::user::config::CaretStyle;
+use crate::user::stats::Tags;
+use crate::{Config, Theme};
+use crate::user::Stats;
 
 use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use ratatui::{
@@ -10,6 +16,24 @@ use ratatui::{
     widgets::{Block, Clear, List, ListState, Paragraph},
 };
 
+const TIMES: [usize; 4] = [15, 30, 60, 120];
+const WORD_COUNTS: [usize; 4] = [10, 25, 50, 100];
+const QUOTE_LENGTHS: [QuoteLength; 5] = [
+    QuoteLength::All,
+    QuoteLength::Short,
+    QuoteLength::Medium,
+    QuoteLength::Long,
+    QuoteLength::Thicc,
+];
+
+pub enum QuickMenuAction {
+    /// Start the challenge with this name.
+    StartChallenge(String),
+    StopChallenge,
+    /// A setting of the config was changed.
+    ConfigChanged,
+}
+
 pub struct QuickMenu {
     visible: bool,
     input: Vec<char>,
@@ -17,13 +41,64 @@ pub struct QuickMenu {
     history: Vec<Vec<char>>,
     list_state: ListState,
     options: QuickMenuItem,
+    layout_names: Vec<String>,
+    click_sounds: Vec<String>,
+    error_sounds: Vec<String>,
+    /// Why themes, layouts, sounds or challenges are missing from the menu.
+    errors: Vec<crate::Error>,
 }
 
 impl QuickMenu {
-    pub fn new() -> Self {
-        let options = Theme::all_quick_menu_items()
-            .unwrap()
-            .label(Some("themes".to_string()));
+    /// The menu with every theme, layout, sound and challenge that can be loaded. The others are
+    /// left out, see [`Self::take_errors`].
+    pub fn new(config: &Config, tags: &Tags) -> Self {
+        let mut errors = Vec::new();
+        let mut or_empty = |result: crate::Result<Vec<String>>| {
+            result.unwrap_or_else(|err| {
+                errors.push(err);
+                Vec::new()
+            })
+        };
+        let layout_names = or_empty(KeyboardLayout::names());
+        let click_sounds = or_empty(audio::sets("click"));
+        let error_sounds = or_empty(audio::sets("error"));
+        let themes = match Theme::all_quick_menu_items() {
+            Ok((themes, theme_errors)) => {
+                errors.extend(theme_errors);
+                themes
+            }
+            Err(err) => {
+                errors.push(err);
+                QuickMenuItem::category(None, Vec::new())
+            }
+        };
+        let themes = themes.label(Some("themes".to_string()));
+        let challenges = Self::challenges_item().unwrap_or_else(|err| {
+            errors.push(err);
+            QuickMenuItem::category(
+                Some("challenges".to_string()),
+                vec![QuickMenuItem::StopChallenge],
+            )
+        });
+        let layouts = layout_names
+            .iter()
+            .cloned()
+            .map(QuickMenuItem::Layout)
+            .collect::<QuickMenuItem>()
+            .label(Some("layouts".to_string()));
+        let options = QuickMenuItem::category(
+            None,
+            vec![
+                themes,
+                Self::tags_item(tags),
+                layouts,
+                Self::settings_item(config),
+                Self::test_item(config, &click_sounds, &error_sounds),
+                Self::funbox_item(config),
+                Self::layoutfluid_item(config, &layout_names),
+                challenges,
+            ],
+        );
         QuickMenu {
             visible: false,
             input: Vec::new(),
@@ -31,9 +106,161 @@ impl QuickMenu {
             history: Vec::new(),
             list_state: ListState::default(),
             options,
+            layout_names,
+            click_sounds,
+            error_sounds,
+            errors,
         }
     }
 
+    /// Errors of everything that couldn't be loaded for the menu, since the last call.
+    pub fn take_errors(&mut self) -> Vec<crate::Error> {
+        std::mem::take(&mut self.errors)
+    }
+
+    fn tags_item(tags: &Tags) -> QuickMenuItem {
+        let mut options: Vec<QuickMenuItem> = tags
+            .iter()
+            .map(|tag| QuickMenuItem::Tag {
+                name: tag.name.clone(),
+                active: tag.active,
+            })
+            .collect();
+        options.push(QuickMenuItem::CreateTag);
+        options.extend(tags.iter().map(|tag| QuickMenuItem::DeleteTag(tag.name.clone())));
+
+        QuickMenuItem::category(Some("tags".to_string()), options)
+    }
+
+    fn settings_item(config: &Config) -> QuickMenuItem {
+        let options = Toggle::ALL
+            .iter()
+            .map(|toggle| QuickMenuItem::Toggle {
+                toggle: *toggle,
+                enabled: toggle.get(config),
+            })
+            .collect();
+
+        QuickMenuItem::category(Some("settings".to_string()), options)
+    }
+
+    /// The settings of the test, each in a category of values to pick from.
+    fn test_item(
+        config: &Config,
+        click_sounds: &[String],
+        error_sounds: &[String],
+    ) -> QuickMenuItem {
+        let category = |label: &str, settings: Vec<Setting>| {
+            let options = settings
+                .into_iter()
+                .map(|setting| QuickMenuItem::Setting {
+                    selected: setting.is_selected(config),
+                    setting,
+                })
+                .collect();
+            QuickMenuItem::category(Some(label.to_string()), options)
+        };
+
+        let modes = TIMES
+            .into_iter()
+            .map(Setting::Time)
+            .chain(WORD_COUNTS.into_iter().map(Setting::Words))
+            .chain(QUOTE_LENGTHS.into_iter().map(Setting::Quote))
+            .collect();
+        let languages = Language::ALL.iter().copied().map(Setting::Language).collect();
+        let difficulties = [Difficulty::Normal, Difficulty::Expert, Difficulty::Master]
+            .into_iter()
+            .map(Setting::Difficulty)
+            .collect();
+        let carets = CaretStyle::ALL.into_iter().map(Setting::Caret).collect();
+        let sounds = std::iter::once(None)
+            .chain(click_sounds.iter().cloned().map(Some))
+            .map(Setting::ClickSound)
+            .chain(
+                std::iter::once(None)
+                    .chain(error_sounds.iter().cloned().map(Some))
+                    .map(Setting::ErrorSound),
+            )
+            .collect();
+
+        QuickMenuItem::category(
+            Some("test".to_string()),
+            vec![
+                category("mode", modes),
+                category("language", languages),
+                category("difficulty", difficulties),
+                category("caret", carets),
+                category("sound", sounds),
+            ],
+        )
+    }
+
+    fn funbox_item(config: &Config) -> QuickMenuItem {
+        let options = funbox::NAMES
+            .iter()
+            .map(|name| QuickMenuItem::Funbox {
+                name: name.to_string(),
+                active: config.funbox.iter().any(|funbox| funbox == name),
+            })
+            .collect();
+
+        QuickMenuItem::category(Some("funbox".to_string()), options)
+    }
+
+    fn layoutfluid_item(config: &Config, layout_names: &[String]) -> QuickMenuItem {
+        let options = layout_names
+            .iter()
+            .map(|name| QuickMenuItem::LayoutfluidLayout {
+                name: name.clone(),
+                selected: config.layoutfluid_layouts.contains(name),
+            })
+            .collect();
+
+        QuickMenuItem::category(Some("layoutfluid".to_string()), options)
+    }
+
+    /// The challenges that can be played with the funboxes that exist here.
+    fn challenges_item() -> crate::Result<QuickMenuItem> {
+        let mut options: Vec<QuickMenuItem> = Challenge::all()?
+            .into_iter()
+            .filter(Challenge::is_supported)
+            .map(|challenge| QuickMenuItem::Challenge {
+                name: challenge.name,
+                display: challenge.display,
+            })
+            .collect();
+        options.push(QuickMenuItem::StopChallenge);
+
+        Ok(QuickMenuItem::category(Some("challenges".to_string()), options))
+    }
+
+    /// Replaces the top level category with the same label as `category`.
+    fn replace_category(&mut self, category: QuickMenuItem) {
+        let QuickMenuItem::Category { options, .. } = &mut self.options else {
+            return;
+        };
+        let QuickMenuItem::Category { label, .. } = &category else {
+            return;
+        };
+        let old = options.iter_mut().find(|option| {
+            matches!(option, QuickMenuItem::Category { label: old, .. } if old == label)
+        });
+        if let Some(old) = old {
+            *old = category;
+        }
+    }
+
+    pub fn refresh_tags(&mut self, tags: &Tags) {
+        self.replace_category(Self::tags_item(tags));
+    }
+
+    pub fn refresh_settings(&mut self, config: &Config) {
+        self.replace_category(Self::settings_item(config));
+        self.replace_category(Self::test_item(config, &self.click_sounds, &self.error_sounds));
+        self.replace_category(Self::funbox_item(config));
+        self.replace_category(Self::layoutfluid_item(config, &self.layout_names));
+    }
+
     pub fn is_visible(&self) -> bool {
         self.visible
     }
@@ -42,7 +269,13 @@ impl QuickMenu {
         self.visible = !self.visible;
     }
 
-    pub fn handle_key_event(&mut self, key_event: KeyEvent, config: &mut Config) -> crate::Result<()> {
+    pub fn handle_key_event(
+        &mut self,
+        key_event: KeyEvent,
+        config: &mut Config,
+        stats: &mut Stats,
+    ) -> crate::Result<Option<QuickMenuAction>> {
+        let mut action = None;
         match key_event.kind {
             KeyEventKind::Press => match key_event.modifiers {
                 KeyModifiers::CONTROL => match key_event.code {
@@ -73,7 +306,7 @@ impl QuickMenu {
                         }
                     }
                     KeyCode::Enter => {
-                        self.select(config);
+                        action = self.select(config, stats)?;
                     }
                     _ => (),
                 },
@@ -82,12 +315,78 @@ impl QuickMenu {
             _ => (),
         }
 
-        Ok(())
+        Ok(action)
     }
 
-    fn select(&self, config: &mut Config) {
-        if let Some(current) = self.list_state.selected() {
+    fn select(
+        &mut self,
+        config: &mut Config,
+        stats: &mut Stats,
+    ) -> crate::Result<Option<QuickMenuAction>> {
+        let Some(current) = self.list_state.selected() else {
+            return Ok(None);
+        };
+        let words = self.format_input();
+        let items = self.options.filtered(None, &words);
+        let Some((_, item)) = items.get(current) else {
+            return Ok(None);
+        };
+
+        let mut action = Some(QuickMenuAction::ConfigChanged);
+        match item {
+            QuickMenuItem::Challenge { name, .. } => {
+                self.visible = false;
+                return Ok(Some(QuickMenuAction::StartChallenge(name.clone())));
+            }
+            QuickMenuItem::StopChallenge => {
+                self.visible = false;
+                return Ok(Some(QuickMenuAction::StopChallenge));
+            }
+            QuickMenuItem::Theme(theme) => {
+                config.style.theme = theme.clone();
+                self.visible = false;
+            }
+            QuickMenuItem::Layout(name) => {
+                config.layout = KeyboardLayout::load(name)?;
+                self.visible = false;
+            }
+            QuickMenuItem::Toggle { toggle, enabled } => toggle.set(config, !enabled),
+            QuickMenuItem::Setting { setting, .. } => setting.apply(config),
+            QuickMenuItem::Funbox { name, active } => {
+                if *active {
+                    config.funbox.retain(|funbox| funbox != name);
+                } else {
+                    config.funbox.push(name.clone());
+                }
+            }
+            // Layouts are used in the order they were picked.
+            QuickMenuItem::LayoutfluidLayout { name, selected } => {
+                if *selected {
+                    config.layoutfluid_layouts.retain(|layout| layout != name);
+                } else {
+                    config.layoutfluid_layouts.push(name.clone());
+                }
+            }
+            QuickMenuItem::Tag { name, .. } => {
+                stats.tags_mut().toggle(name)?;
+                action = None;
+            }
+            QuickMenuItem::CreateTag => {
+                stats.tags_mut().create(&words.join(" "))?;
+                self.input.clear();
+                self.current_index = 0;
+                action = None;
+            }
+            QuickMenuItem::DeleteTag(name) => {
+                stats.tags_mut().remove(name)?;
+                action = None;
+            }
+            QuickMenuItem::Category { .. } => action = None,
         }
+
+        self.refresh_tags(stats.tags());
+        self.refresh_settings(config);
+        Ok(action)
     }
 
     fn format_input(&self) -> Vec<String> {
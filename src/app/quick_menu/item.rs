@@ -1,7 +1,126 @@
-use crate::{Theme, typing::Mode};
+use crate::monkeytype::Language;
+use crate::typing::{Difficulty, QuoteLength};
+use crate::user::config::CaretStyle;
+use crate::user::stats::ModeKind;
+use crate::{Config, Theme};
 
 use ratatui::{prelude::*, widgets::ListItem};
 
+/// Separates a category from the items in it in the labels.
+const SEPARATOR: &str = "󰅂 ";
+
+/// A setting that can be turned on and off from the quick menu.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Toggle {
+    Keymap,
+    EmulateLayout,
+    Punctuation,
+    Numbers,
+}
+
+impl Toggle {
+    pub const ALL: [Toggle; 4] = [
+        Self::Keymap,
+        Self::EmulateLayout,
+        Self::Punctuation,
+        Self::Numbers,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Keymap => "keymap",
+            Self::EmulateLayout => "emulate layout",
+            Self::Punctuation => "punctuation",
+            Self::Numbers => "numbers",
+        }
+    }
+
+    pub fn get(&self, config: &Config) -> bool {
+        match self {
+            Self::Keymap => config.keymap,
+            Self::EmulateLayout => config.emulate_layout,
+            Self::Punctuation => config.punctuation,
+            Self::Numbers => config.numbers,
+        }
+    }
+
+    pub fn set(&self, config: &mut Config, enabled: bool) {
+        match self {
+            Self::Keymap => config.keymap = enabled,
+            Self::EmulateLayout => config.emulate_layout = enabled,
+            Self::Punctuation => config.punctuation = enabled,
+            Self::Numbers => config.numbers = enabled,
+        }
+    }
+}
+
+/// A value of a test setting that can be picked from the quick menu.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Setting {
+    Time(usize),
+    Words(usize),
+    Quote(QuoteLength),
+    Language(Language),
+    Difficulty(Difficulty),
+    Caret(CaretStyle),
+    /// A click sound set, `None` turns the clicks off.
+    ClickSound(Option<String>),
+    /// An error sound set, `None` plays the click on mistakes.
+    ErrorSound(Option<String>),
+}
+
+impl Setting {
+    pub fn label(&self) -> String {
+        match self {
+            Self::Time(seconds) => format!("time {seconds}"),
+            Self::Words(words) => format!("words {words}"),
+            Self::Quote(length) => format!("quote {length}"),
+            Self::Language(language) => language.to_string(),
+            Self::Difficulty(difficulty) => difficulty.to_string(),
+            Self::Caret(caret) => caret.to_string(),
+            Self::ClickSound(name) => name.clone().unwrap_or_else(|| "click off".to_string()),
+            Self::ErrorSound(name) => name.clone().unwrap_or_else(|| "error off".to_string()),
+        }
+    }
+
+    pub fn is_selected(&self, config: &Config) -> bool {
+        match self {
+            Self::Time(seconds) => config.mode == ModeKind::Time && config.length == *seconds,
+            Self::Words(words) => config.mode == ModeKind::Words && config.length == *words,
+            Self::Quote(length) => {
+                config.mode == ModeKind::Quote && config.quote_lengths == [length.clone()]
+            }
+            Self::Language(language) => config.language == *language,
+            Self::Difficulty(difficulty) => config.difficulty == *difficulty,
+            Self::Caret(caret) => config.style.caret == *caret,
+            Self::ClickSound(name) => config.click_sound == *name,
+            Self::ErrorSound(name) => config.error_sound == *name,
+        }
+    }
+
+    pub fn apply(&self, config: &mut Config) {
+        match self {
+            Self::Time(seconds) => {
+                config.mode = ModeKind::Time;
+                config.length = *seconds;
+            }
+            Self::Words(words) => {
+                config.mode = ModeKind::Words;
+                config.length = *words;
+            }
+            Self::Quote(length) => {
+                config.mode = ModeKind::Quote;
+                config.quote_lengths = vec![length.clone()];
+            }
+            Self::Language(language) => config.language = *language,
+            Self::Difficulty(difficulty) => config.difficulty = *difficulty,
+            Self::Caret(caret) => config.style.caret = *caret,
+            Self::ClickSound(name) => config.click_sound = name.clone(),
+            Self::ErrorSound(name) => config.error_sound = name.clone(),
+        }
+    }
+}
+
 pub enum QuickMenuItem {
     Category {
         label: Option<String>,
@@ -9,7 +128,19 @@ pub enum QuickMenuItem {
     },
     // IntInput { label: String, input: Vec<usize>, placeholder: String },
     Theme(Theme),
-    Mode(Mode),
+    /// Name of a keyboard layout.
+    Layout(String),
+    Setting { setting: Setting, selected: bool },
+    Tag { name: String, active: bool },
+    Toggle { toggle: Toggle, enabled: bool },
+    Funbox { name: String, active: bool },
+    Challenge { name: String, display: String },
+    StopChallenge,
+    /// A layout that can be added to or removed from the layoutfluid layouts.
+    LayoutfluidLayout { name: String, selected: bool },
+    /// Creates a tag named like the current input.
+    CreateTag,
+    DeleteTag(String),
 }
 
 impl QuickMenuItem {
@@ -47,25 +178,58 @@ impl QuickMenuItem {
         list_width: u16,
         words: &[String],
     ) -> Vec<ListItem> {
-        match self {
+        self.filtered(in_category, words)
+            .into_iter()
+            .map(|(label, item)| item.as_list_item(label, list_width))
+            .collect()
+    }
+
+    /// All selectable items matching `words`, in the order they are listed, together with their
+    /// full label.
+    pub fn filtered(&self, in_category: Option<&str>, words: &[String]) -> Vec<(String, &Self)> {
+        let name = match self {
             Self::Category { label, options } => {
                 let category = Self::add_to_category(in_category, label.as_deref());
-                let items: Vec<_> = options
+                return options
                     .iter()
-                    .flat_map(|opt| opt.as_list_items_filtered(category.as_deref(), list_width, words))
+                    .flat_map(|opt| opt.filtered(category.as_deref(), words))
                     .collect();
-
-                items
             }
             // Self::IntInput { label, .. } => {
             // },
-            Self::Theme(theme) => {
-                let category =
-                    Self::add_to_category(in_category, Some(&theme.name)).unwrap_or_default();
+            Self::CreateTag => {
+                if words.is_empty() {
+                    return Vec::new();
+                }
+                let name = format!("create \"{}\"", words.join(" "));
+                let label = Self::add_to_category(in_category, Some(&name)).unwrap_or_default();
+                return vec![(label, self)];
+            }
+            Self::Setting { setting, .. } => setting.label(),
+            Self::Theme(theme) => theme.name.clone(),
+            Self::Layout(name) => name.clone(),
+            Self::Toggle { toggle, .. } => toggle.label().to_string(),
+            Self::Funbox { name, .. } => name.clone(),
+            Self::Challenge { display, .. } => display.clone(),
+            Self::StopChallenge => "stop challenge".to_string(),
+            Self::LayoutfluidLayout { name, .. } => name.clone(),
+            Self::Tag { name, .. } => name.clone(),
+            Self::DeleteTag(name) => format!("delete{SEPARATOR}{name}"),
+        };
 
-                let matches = Self::matches(words, &category);
-                let current_len = category.len();
-                let mut line = Line::raw(category);
+        let label = Self::add_to_category(in_category, Some(&name)).unwrap_or_default();
+        if Self::matches(words, &label) {
+            vec![(label, self)]
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn as_list_item(&self, label: String, list_width: u16) -> ListItem<'_> {
+        match self {
+            Self::Theme(theme) => {
+                let current_len = label.len();
+                let mut line = Line::raw(label);
 
                 line.push_span(Span::raw(format!("{:>1$}", " ", list_width as usize - current_len - 4)));
                 line.push_span(Span::styled(" ", Style::new().bg(theme.main)));
@@ -74,21 +238,24 @@ impl QuickMenuItem {
                 line.push_span(Span::raw(" "));
                 line.push_span(Span::styled(" ", Style::new().bg(theme.text)));
 
-                if matches {
-                    vec![ListItem::new(line)]
-                } else {
-                    Vec::new()
-                }
+                ListItem::new(line)
             }
-            Self::Mode(mode) => {
-                Vec::new()
+            Self::Tag { active, .. }
+            | Self::Toggle { enabled: active, .. }
+            | Self::Funbox { active, .. }
+            | Self::Setting { selected: active, .. }
+            | Self::LayoutfluidLayout { selected: active, .. } => {
+                let padding = (list_width as usize).saturating_sub(label.chars().count() + 5);
+                let check = if *active { "[x]" } else { "[ ]" };
+                ListItem::new(Line::raw(format!("{label}{:>padding$}{check}", "")))
             }
+            _ => ListItem::new(Line::raw(label)),
         }
     }
 
     fn add_to_category(category: Option<&str>, text: Option<&str>) -> Option<String> {
         match (category, text) {
-            (Some(category), Some(text)) => Some(format!("{category}󰅂 {text}")),
+            (Some(category), Some(text)) => Some(format!("{category}{SEPARATOR}{text}")),
             (None, Some(text)) => Some(text.to_string()),
             (Some(category), None) => Some(category.to_string()),
             (None, None) => None,
@@ -0,0 +1,769 @@
+use crate::monkeytype::{Challenge, Language};
+use crate::typing::{Heatmap, KEYBOARD_WIDTH, Keyboard};
+use crate::user::Stats;
+use crate::user::stats::filter::{self, Filter};
+use crate::user::stats::{
+    ExportFormat, ModeKind, PersonalBest, PersonalBests, Progress, TestResult,
+};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::{
+    prelude::*,
+    widgets::{Axis, Block, Cell, Chart, Dataset, GraphType, Paragraph, Row, Table, TableState},
+};
+
+use std::cmp::Ordering;
+
+const MOVING_AVERAGE_WINDOW: usize = 10;
+/// Keys and bigrams typed less often are left out of the key statistics.
+const MIN_KEY_SAMPLES: u32 = 10;
+const PROGRESS_BAR_WIDTH: usize = 20;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortColumn {
+    Date,
+    Mode,
+    Language,
+    Wpm,
+    RawWpm,
+    Accuracy,
+    Consistency,
+    Duration,
+}
+
+impl SortColumn {
+    fn next(self) -> Self {
+        match self {
+            Self::Date => Self::Mode,
+            Self::Mode => Self::Language,
+            Self::Language => Self::Wpm,
+            Self::Wpm => Self::RawWpm,
+            Self::RawWpm => Self::Accuracy,
+            Self::Accuracy => Self::Consistency,
+            Self::Consistency => Self::Duration,
+            Self::Duration => Self::Date,
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            Self::Date => "date",
+            Self::Mode => "mode",
+            Self::Language => "language",
+            Self::Wpm => "wpm",
+            Self::RawWpm => "raw",
+            Self::Accuracy => "acc",
+            Self::Consistency => "cons",
+            Self::Duration => "time",
+        }
+    }
+
+    fn compare(&self, a: &TestResult, b: &TestResult) -> Ordering {
+        match self {
+            Self::Date => a.timestamp.cmp(&b.timestamp),
+            Self::Mode => (a.mode as usize, a.length).cmp(&(b.mode as usize, b.length)),
+            Self::Language => a.language.to_string().cmp(&b.language.to_string()),
+            Self::Wpm => a.wpm.total_cmp(&b.wpm),
+            Self::RawWpm => a.raw_wpm.total_cmp(&b.raw_wpm),
+            Self::Accuracy => a.accuracy.total_cmp(&b.accuracy),
+            Self::Consistency => a.consistency.total_cmp(&b.consistency),
+            Self::Duration => a.duration.total_cmp(&b.duration),
+        }
+    }
+
+    const ALL: [SortColumn; 8] = [
+        Self::Date,
+        Self::Mode,
+        Self::Language,
+        Self::Wpm,
+        Self::RawWpm,
+        Self::Accuracy,
+        Self::Consistency,
+        Self::Duration,
+    ];
+}
+
+pub enum StatsAction {
+    /// Play the replay of the test with the given timestamp.
+    Replay(u64),
+}
+
+/// The filtered and sorted history with its personal bests, kept between draws until the
+/// filter, the sorting or the history changes.
+struct Cache {
+    filter: Filter,
+    sort: SortColumn,
+    descending: bool,
+    history_len: usize,
+    /// Indices into the history of the results matching the filter, oldest first.
+    filtered: Vec<usize>,
+    /// `filtered`, sorted like the table.
+    rows: Vec<usize>,
+    /// Personal bests of the filtered results, sorted by mode, length and language.
+    personal_bests: Vec<PersonalBest>,
+    /// Index into `rows` of the first row in the table viewport.
+    offset: usize,
+    /// Formatted cells of the rows in the viewport, and whether their test was completed.
+    visible: Vec<([String; 8], bool)>,
+    /// Progress towards the open challenges as indices into the challenges, most advanced
+    /// first. Computed when the achievements are shown.
+    progress: Option<Vec<(usize, Progress)>>,
+}
+
+pub struct StatsView {
+    filter: Filter,
+    sort: SortColumn,
+    descending: bool,
+    table_state: TableState,
+    /// Result of the last export.
+    notice: Option<String>,
+    heatmap: Heatmap,
+    /// Whether the achievements are shown instead of the chart and the keys.
+    show_achievements: bool,
+    /// Loaded when the achievements are shown for the first time.
+    challenges: Vec<Challenge>,
+    cache: Option<Cache>,
+}
+
+impl StatsView {
+    pub fn new() -> Self {
+        Self {
+            filter: Filter::default(),
+            sort: SortColumn::Date,
+            descending: true,
+            table_state: TableState::default().with_selected(0),
+            notice: None,
+            heatmap: Heatmap::default(),
+            show_achievements: false,
+            challenges: Vec::new(),
+            cache: None,
+        }
+    }
+
+    pub fn handle_key_event(&mut self, key_event: KeyEvent, stats: &Stats) -> Option<StatsAction> {
+        if key_event.kind != KeyEventKind::Press
+            || !matches!(
+                key_event.modifiers,
+                KeyModifiers::NONE | KeyModifiers::SHIFT
+            )
+        {
+            return None;
+        }
+
+        match key_event.code {
+            KeyCode::Char('j') | KeyCode::Down => self.table_state.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => self.table_state.select_previous(),
+            KeyCode::Char('g') => self.table_state.select_first(),
+            KeyCode::Char('G') => self.table_state.select_last(),
+            KeyCode::Char('s') => self.sort = self.sort.next(),
+            KeyCode::Char('r') => self.descending = !self.descending,
+            KeyCode::Char('m') => {
+                self.filter.mode = match self.filter.mode {
+                    None => Some(ModeKind::Time),
+                    Some(ModeKind::Time) => Some(ModeKind::Words),
+                    Some(ModeKind::Words) => Some(ModeKind::Quote),
+                    Some(ModeKind::Quote) => Some(ModeKind::Custom),
+                    Some(ModeKind::Custom) => None,
+                }
+            }
+            KeyCode::Char('l') => self.filter.language = next_language(stats, self.filter.language),
+            KeyCode::Char('d') => self.filter.date_range = self.filter.date_range.next(),
+            KeyCode::Char('t') => self.filter.tag = next_tag(stats, self.filter.tag.take()),
+            KeyCode::Char('e') => self.export(stats, ExportFormat::Csv),
+            KeyCode::Char('E') => self.export(stats, ExportFormat::Json),
+            KeyCode::Char('h') => self.heatmap = self.heatmap.next(),
+            KeyCode::Char('a') => self.toggle_achievements(),
+            KeyCode::Enter => {
+                let cache = self.cached(stats);
+                let last = cache.rows.len().saturating_sub(1);
+                let action = (self.table_state.selected())
+                    .and_then(|selected| cache.rows.get(selected.min(last)))
+                    .map(|&i| StatsAction::Replay(stats.history()[i].timestamp));
+                self.cache = Some(cache);
+                return action;
+            }
+            _ => (),
+        }
+
+        None
+    }
+
+    fn export(&mut self, stats: &Stats, format: ExportFormat) {
+        let path = stats.exports_dir().join(format!(
+            "results-{}.{}",
+            filter::now_millis(),
+            format.extension()
+        ));
+
+        self.notice = Some(match stats.export(&self.filter, &path, format) {
+            Ok(count) => format!("exported {count} results to {}", path.display()),
+            Err(err) => format!("export failed: {err}"),
+        });
+    }
+
+    fn toggle_achievements(&mut self) {
+        self.show_achievements = !self.show_achievements;
+        if self.show_achievements && self.challenges.is_empty() {
+            match Challenge::all() {
+                Ok(challenges) => self.challenges = challenges,
+                Err(err) => self.notice = Some(format!("loading challenges failed: {err}")),
+            }
+        }
+    }
+
+    /// Takes the cache, rebuilt if it is out of date.
+    fn cached(&mut self, stats: &Stats) -> Cache {
+        let history = stats.history();
+        if let Some(cache) = self.cache.take()
+            && cache.filter == self.filter
+            && cache.sort == self.sort
+            && cache.descending == self.descending
+            && cache.history_len == history.len()
+        {
+            return cache;
+        }
+
+        let filtered: Vec<usize> = (0..history.len())
+            .filter(|&i| self.filter.matches(&history[i]))
+            .collect();
+        let mut rows = filtered.clone();
+        rows.sort_by(|&a, &b| self.sort.compare(&history[a], &history[b]));
+        if self.descending {
+            rows.reverse();
+        }
+
+        // Computed from the filtered results, so that the bests follow the tag filter as well.
+        let mut personal_bests: Vec<PersonalBest> =
+            PersonalBests::rebuild(filtered.iter().map(|&i| &history[i]))
+                .iter()
+                .cloned()
+                .collect();
+        personal_bests.sort_by_key(|pb| {
+            (
+                pb.key.mode as usize,
+                pb.key.length,
+                pb.key.language.to_string(),
+            )
+        });
+
+        Cache {
+            filter: self.filter.clone(),
+            sort: self.sort,
+            descending: self.descending,
+            history_len: history.len(),
+            filtered,
+            rows,
+            personal_bests,
+            offset: 0,
+            visible: Vec::new(),
+            progress: None,
+        }
+    }
+
+    pub fn render(&mut self, stats: &Stats, config: &crate::Config, area: Rect, buf: &mut Buffer) {
+        let style = &config.style;
+        let [filters, top, bottom] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Percentage(60),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+        let [table, aggregates] =
+            Layout::horizontal([Constraint::Fill(1), Constraint::Length(36)]).areas(top);
+        let [chart, keys] =
+            Layout::horizontal([Constraint::Fill(1), Constraint::Length(KEYBOARD_WIDTH + 24)])
+                .areas(bottom);
+
+        let mut cache = self.cached(stats);
+        let history = stats.history();
+        let filtered: Vec<&TestResult> = cache.filtered.iter().map(|&i| &history[i]).collect();
+
+        self.render_filters(style, filters, buf);
+        self.render_table(&mut cache, history, style, table, buf);
+        Self::render_aggregates(&filtered, &cache.personal_bests, style, aggregates, buf);
+        if self.show_achievements {
+            self.render_achievements(&mut cache, stats, style, bottom, buf);
+        } else {
+            Self::render_chart(&filtered, style, chart, buf);
+            self.render_keys(stats, config, keys, buf);
+        }
+        self.cache = Some(cache);
+    }
+
+    fn render_filters(&self, style: &crate::Style, area: Rect, buf: &mut Buffer) {
+        let mode = self.filter.mode.map(|m| m.to_string());
+        let language = self.filter.language.map(|l| l.to_string());
+        let key = Style::new().fg(style.theme.main);
+        let value = Style::new().fg(style.theme.text);
+
+        Line::from_iter([
+            Span::styled("[m] ", key),
+            Span::styled(mode.as_deref().unwrap_or("all modes"), value),
+            Span::styled("  [l] ", key),
+            Span::styled(language.as_deref().unwrap_or("all languages"), value),
+            Span::styled("  [d] ", key),
+            Span::styled(self.filter.date_range.to_string(), value),
+            Span::styled("  [t] ", key),
+            Span::styled(self.filter.tag.as_deref().unwrap_or("all tags"), value),
+            Span::styled("  [s/r] ", key),
+            Span::styled(
+                format!(
+                    "{} {}",
+                    self.sort.title(),
+                    if self.descending { "desc" } else { "asc" }
+                ),
+                value,
+            ),
+            Span::styled("  [enter] ", key),
+            Span::styled("replay", value),
+            Span::styled("  [e/E] ", key),
+            Span::styled("export csv/json", value),
+            Span::styled("  [a] ", key),
+            Span::styled(
+                if self.show_achievements { "chart" } else { "achievements" },
+                value,
+            ),
+            Span::styled(
+                format!("  {}", self.notice.as_deref().unwrap_or_default()),
+                Style::new().fg(style.theme.sub),
+            ),
+        ])
+        .render(area, buf);
+    }
+
+    /// Only the rows in the viewport are formatted, and only when the viewport moves.
+    fn render_table(
+        &mut self,
+        cache: &mut Cache,
+        history: &[TestResult],
+        style: &crate::Style,
+        area: Rect,
+        buf: &mut Buffer,
+    ) {
+        // The borders and the header take three lines.
+        let height = area.height.saturating_sub(3).max(1) as usize;
+        let len = cache.rows.len();
+        let selected = self
+            .table_state
+            .selected()
+            .map(|selected| selected.min(len.saturating_sub(1)));
+        self.table_state.select(selected);
+        let mut offset = self.table_state.offset().min(len.saturating_sub(height));
+        if let Some(selected) = selected {
+            offset = offset.clamp((selected + 1).saturating_sub(height), selected);
+        }
+        *self.table_state.offset_mut() = offset;
+
+        let end = (offset + height).min(len);
+        if cache.offset != offset || cache.visible.len() != end - offset {
+            cache.offset = offset;
+            cache.visible = cache.rows[offset..end]
+                .iter()
+                .map(|&i| {
+                    let r = &history[i];
+                    let cells = [
+                        filter::format_timestamp(r.timestamp),
+                        format!("{} {}", r.mode, r.length),
+                        r.language.to_string(),
+                        format!("{:.2}", r.wpm),
+                        format!("{:.2}", r.raw_wpm),
+                        format!("{:.2}%", r.accuracy),
+                        format!("{:.0}%", r.consistency),
+                        format!("{:.1}s", r.duration),
+                    ];
+                    (cells, r.is_completed())
+                })
+                .collect();
+        }
+
+        let header = Row::new(SortColumn::ALL.iter().map(|column| {
+            let cell = Cell::from(column.title());
+            if *column == self.sort {
+                cell.style(Style::new().fg(style.theme.main).bold())
+            } else {
+                cell
+            }
+        }))
+        .style(Style::new().fg(style.theme.sub));
+
+        let table_rows = cache.visible.iter().map(|(cells, completed)| {
+            let row = Row::new(cells.iter().map(String::as_str));
+            if *completed {
+                row.style(Style::new().fg(style.theme.text))
+            } else {
+                row.style(Style::new().fg(style.theme.untyped_letter))
+            }
+        });
+
+        let table = Table::new(
+            table_rows,
+            [
+                Constraint::Length(16),
+                Constraint::Length(9),
+                Constraint::Fill(1),
+                Constraint::Length(7),
+                Constraint::Length(7),
+                Constraint::Length(7),
+                Constraint::Length(5),
+                Constraint::Length(7),
+            ],
+        )
+        .header(header)
+        .block(
+            Block::bordered()
+                .title(format!(" history ({len}) "))
+                .border_type(style.border_type)
+                .border_style(Style::new().fg(style.theme.sub)),
+        )
+        .row_highlight_style(Style::new().fg(style.theme.bg).bg(style.theme.main));
+
+        // The table only gets the visible rows, so the selection is relative to the viewport.
+        let mut state = TableState::default().with_selected(selected.map(|s| s - offset));
+        StatefulWidget::render(table, area, buf, &mut state);
+    }
+
+    fn render_aggregates(
+        filtered: &[&TestResult],
+        personal_bests: &[PersonalBest],
+        style: &crate::Style,
+        area: Rect,
+        buf: &mut Buffer,
+    ) {
+        let completed: Vec<&TestResult> = filtered
+            .iter()
+            .copied()
+            .filter(|r| r.is_completed())
+            .collect();
+        let seconds: f64 = filtered.iter().map(|r| r.duration).sum();
+        let seconds = seconds as u64;
+
+        let label = Style::new().fg(style.theme.sub);
+        let value = Style::new().fg(style.theme.text);
+        let entry = |name: &str, text: String| {
+            Line::from_iter([
+                Span::styled(format!("{name:<18}"), label),
+                Span::styled(text, value),
+            ])
+        };
+
+        let mut lines = vec![
+            entry("tests started", filtered.len().to_string()),
+            entry("tests completed", completed.len().to_string()),
+            entry(
+                "time typing",
+                format!(
+                    "{:02}:{:02}:{:02}",
+                    seconds / 3600,
+                    seconds / 60 % 60,
+                    seconds % 60
+                ),
+            ),
+        ];
+
+        for count in [10, 100] {
+            let last = &completed[completed.len().saturating_sub(count)..];
+            let (wpm, acc) = averages(last);
+            lines.push(entry(
+                &format!("avg last {count}"),
+                format!("{wpm:.2} wpm {acc:.2}%"),
+            ));
+        }
+
+        lines.push(Line::default());
+        lines.push(Line::styled(
+            "personal bests",
+            Style::new().fg(style.theme.main),
+        ));
+        for pb in personal_bests {
+            let mut name = format!("{} {}", pb.key.mode, pb.key.length);
+            if pb.key.punctuation {
+                name.push_str(" p");
+            }
+            if pb.key.numbers {
+                name.push_str(" n");
+            }
+            lines.push(entry(
+                &name,
+                format!("{:.2} wpm {:.0}%", pb.wpm, pb.accuracy),
+            ));
+        }
+
+        Paragraph::new(lines)
+            .block(
+                Block::bordered()
+                    .border_type(style.border_type)
+                    .border_style(Style::new().fg(style.theme.sub)),
+            )
+            .render(area, buf);
+    }
+
+    fn render_keys(&self, stats: &Stats, config: &crate::Config, area: Rect, buf: &mut Buffer) {
+        let style = &config.style;
+        // Keystrokes aren't stored per result, so these always cover all tests.
+        let block = Block::bordered()
+            .title(" keys (all tests) ")
+            .border_type(style.border_type)
+            .border_style(Style::new().fg(style.theme.sub));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let [lists, title, keyboard] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(1),
+            Constraint::Length(5),
+        ])
+        .areas(inner);
+
+        stats.key_stats().render(MIN_KEY_SAMPLES, style, lists, buf);
+        Line::from_iter([
+            Span::styled("[h] ", Style::new().fg(style.theme.main)),
+            Span::styled(
+                format!("{} on {}", self.heatmap, config.layout.name),
+                Style::new().fg(style.theme.sub),
+            ),
+        ])
+        .render(title, buf);
+        Keyboard::new(&config.layout, style)
+            .heatmap(stats.key_stats(), self.heatmap, MIN_KEY_SAMPLES)
+            .render(keyboard, buf);
+    }
+
+    /// Completed challenges with their date and result, followed by the progress towards the
+    /// others. Earned challenges that give a role on monkeytype are shown as badges on top.
+    fn render_achievements(
+        &self,
+        cache: &mut Cache,
+        stats: &Stats,
+        style: &crate::Style,
+        area: Rect,
+        buf: &mut Buffer,
+    ) {
+        let achievements = stats.achievements();
+        let label = Style::new().fg(style.theme.sub);
+        let value = Style::new().fg(style.theme.text);
+
+        let badges = self
+            .challenges
+            .iter()
+            .filter(|c| c.auto_role && achievements.get(&c.name).is_some())
+            .flat_map(|c| {
+                [
+                    Span::styled(
+                        format!(" {} ", c.display),
+                        Style::new().fg(style.theme.bg).bg(style.theme.main),
+                    ),
+                    Span::raw(" "),
+                ]
+            });
+        let mut lines = vec![Line::from_iter(badges), Line::default()];
+
+        let mut earned: Vec<_> = achievements.iter().collect();
+        earned.sort_by_key(|a| a.result.timestamp);
+        for achievement in &earned {
+            let display = self
+                .challenges
+                .iter()
+                .find(|c| c.name == achievement.challenge)
+                .map_or(achievement.challenge.as_str(), |c| c.display.as_str());
+            let result = &achievement.result;
+            lines.push(Line::from_iter([
+                Span::styled(format!("{display:<32}"), Style::new().fg(style.theme.main)),
+                Span::styled(format!("{}  ", filter::format_timestamp(result.timestamp)), label),
+                Span::styled(
+                    format!(
+                        "{:.2} wpm {:.2}% {:.0}s",
+                        result.wpm, result.accuracy, result.duration
+                    ),
+                    value,
+                ),
+            ]));
+        }
+
+        // Left uncomputed until the challenges are loaded, which may fail the first time.
+        if cache.progress.is_none() && !self.challenges.is_empty() {
+            let mut open: Vec<_> = (self.challenges.iter().enumerate())
+                .filter(|(_, c)| c.is_supported() && achievements.get(&c.name).is_none())
+                .filter_map(|(i, c)| Some((i, Progress::new(c, stats.history())?)))
+                .collect();
+            open.sort_by(|(_, a), (_, b)| b.ratio().total_cmp(&a.ratio()));
+            cache.progress = Some(open);
+        }
+        for (i, progress) in cache.progress.iter().flatten() {
+            let challenge = &self.challenges[*i];
+            let filled = (progress.ratio() * PROGRESS_BAR_WIDTH as f64) as usize;
+            lines.push(Line::from_iter([
+                Span::styled(format!("{:<32}", challenge.display), label),
+                Span::styled("█".repeat(filled), Style::new().fg(style.theme.main)),
+                Span::styled(
+                    "░".repeat(PROGRESS_BAR_WIDTH - filled),
+                    Style::new().fg(style.theme.sub),
+                ),
+                Span::styled(format!("  {progress}"), value),
+            ]));
+        }
+
+        Paragraph::new(lines)
+            .block(
+                Block::bordered()
+                    .title(format!(
+                        " achievements ({}/{}) ",
+                        earned.len(),
+                        self.challenges.len()
+                    ))
+                    .border_type(style.border_type)
+                    .border_style(Style::new().fg(style.theme.sub)),
+            )
+            .render(area, buf);
+    }
+
+    fn render_chart(filtered: &[&TestResult], style: &crate::Style, area: Rect, buf: &mut Buffer) {
+        let mut completed: Vec<&TestResult> = filtered
+            .iter()
+            .copied()
+            .filter(|r| r.is_completed())
+            .collect();
+        completed.sort_by_key(|r| r.timestamp);
+
+        let wpm: Vec<f64> = completed.iter().map(|r| r.wpm as f64).collect();
+        let acc: Vec<f64> = completed.iter().map(|r| r.accuracy as f64).collect();
+
+        let points = |values: &[f64]| -> Vec<(f64, f64)> {
+            values
+                .iter()
+                .enumerate()
+                .map(|(i, v)| (i as f64, *v))
+                .collect()
+        };
+        let wpm_data = points(&wpm);
+        let wpm_average = points(&moving_average(&wpm, MOVING_AVERAGE_WINDOW));
+        let acc_data = points(&acc);
+        let acc_average = points(&moving_average(&acc, MOVING_AVERAGE_WINDOW));
+
+        let max = wpm.iter().copied().fold(100.0, f64::max).ceil();
+        let len = completed.len().max(1) as f64;
+
+        let datasets = vec![
+            Dataset::default()
+                .name("wpm")
+                .marker(symbols::Marker::Dot)
+                .graph_type(GraphType::Scatter)
+                .style(Style::new().fg(style.theme.sub))
+                .data(&wpm_data),
+            Dataset::default()
+                .name(format!("wpm avg {MOVING_AVERAGE_WINDOW}"))
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::new().fg(style.theme.main))
+                .data(&wpm_average),
+            Dataset::default()
+                .name("acc")
+                .marker(symbols::Marker::Dot)
+                .graph_type(GraphType::Scatter)
+                .style(Style::new().fg(style.theme.sub_alt))
+                .data(&acc_data),
+            Dataset::default()
+                .name(format!("acc avg {MOVING_AVERAGE_WINDOW}"))
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::new().fg(style.theme.text))
+                .data(&acc_average),
+        ];
+
+        Chart::new(datasets)
+            .block(
+                Block::bordered()
+                    .title(" trend ")
+                    .border_type(style.border_type)
+                    .border_style(Style::new().fg(style.theme.sub)),
+            )
+            .x_axis(
+                Axis::default()
+                    .style(Style::new().fg(style.theme.sub))
+                    .bounds([0.0, len])
+                    .labels(["0".to_string(), completed.len().to_string()]),
+            )
+            .y_axis(
+                Axis::default()
+                    .style(Style::new().fg(style.theme.sub))
+                    .bounds([0.0, max])
+                    .labels(["0".to_string(), format!("{}", max / 2.0), max.to_string()]),
+            )
+            .render(area, buf);
+    }
+}
+
+fn next_language(stats: &Stats, current: Option<Language>) -> Option<Language> {
+    let mut languages: Vec<Language> = Vec::new();
+    for result in stats.history() {
+        if !languages.contains(&result.language) {
+            languages.push(result.language);
+        }
+    }
+    languages.sort_by_key(|l| l.to_string());
+
+    match current {
+        None => languages.first().copied(),
+        Some(current) => languages
+            .iter()
+            .position(|l| *l == current)
+            .and_then(|i| languages.get(i + 1))
+            .copied(),
+    }
+}
+
+fn next_tag(stats: &Stats, current: Option<String>) -> Option<String> {
+    let mut tags: Vec<&str> = stats.tags().iter().map(|tag| tag.name.as_str()).collect();
+    for result in stats.history() {
+        tags.extend(result.tags.iter().map(String::as_str));
+    }
+    tags.sort();
+    tags.dedup();
+
+    match current {
+        None => tags.first().map(|tag| tag.to_string()),
+        Some(current) => tags
+            .iter()
+            .position(|tag| *tag == current)
+            .and_then(|i| tags.get(i + 1))
+            .map(|tag| tag.to_string()),
+    }
+}
+
+/// Average wpm and accuracy.
+fn averages(results: &[&TestResult]) -> (f32, f32) {
+    if results.is_empty() {
+        return (0.0, 0.0);
+    }
+    let len = results.len() as f32;
+    let wpm = results.iter().map(|r| r.wpm).sum::<f32>() / len;
+    let acc = results.iter().map(|r| r.accuracy).sum::<f32>() / len;
+    (wpm, acc)
+}
+
+/// Trailing moving average. The first `window - 1` values average over what is available.
+fn moving_average(values: &[f64], window: usize) -> Vec<f64> {
+    let mut sum = 0.0;
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            sum += value;
+            if i >= window {
+                sum -= values[i - window];
+            }
+            sum / (i + 1).min(window) as f64
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moving_average_window() {
+        let values = [1.0, 2.0, 3.0, 4.0, 5.0];
+        assert_eq!(moving_average(&values, 2), vec![1.0, 1.5, 2.5, 3.5, 4.5]);
+        assert_eq!(moving_average(&values, 10), vec![1.0, 1.5, 2.0, 2.5, 3.0]);
+    }
+}
@@ -7,15 +7,20 @@ use ratatui::style::{Color, Stylize};
 pub enum Tab {
     Main,
     Typing,
+    Stats,
     Settings,
     Help,
 }
 
 impl Tab {
+    /// The tabs shown in the heading, in the order of their numbers.
+    pub const HEADING: [Tab; 3] = [Tab::Typing, Tab::Stats, Tab::Settings];
+
     pub fn from_number(num: usize) -> Option<Tab> {
         match num {
             n if n == Tab::Main as usize => Some(Tab::Main),
             n if n == Tab::Typing as usize => Some(Tab::Typing),
+            n if n == Tab::Stats as usize => Some(Tab::Stats),
             n if n == Tab::Settings as usize => Some(Tab::Settings),
             n if n == Tab::Help as usize => Some(Tab::Help),
             _ => None,
@@ -28,6 +33,7 @@ impl Tab {
             Self::Typing => "Typing",
             Self::Help => "Help",
             Self::Settings => "Settings",
+            Self::Stats => "Stats",
         }
     }
 
@@ -0,0 +1,106 @@
+//! The `data` directory, compressed into the binary by the build script. Files are decompressed
+//! the first time they are read and kept afterwards, so a big word list costs nothing until it
+//! is selected.
+
+use crate::dirs::Entry;
+
+use std::path::{Component, Path};
+use std::sync::{LazyLock, OnceLock};
+
+/// Paths relative to `data`, with `/` between the components, and the compressed files. Sorted
+/// by path.
+static ASSETS: &[(&str, &[u8])] = include!(concat!(env!("OUT_DIR"), "/assets.rs"));
+
+/// The decompressed files, at the same index as in [`ASSETS`].
+static DECOMPRESSED: LazyLock<Vec<OnceLock<Vec<u8>>>> =
+    LazyLock::new(|| ASSETS.iter().map(|_| OnceLock::new()).collect());
+
+fn key(relative: &Path) -> String {
+    let components: Vec<_> = relative
+        .components()
+        .filter(|component| matches!(component, Component::Normal(_)))
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect();
+    components.join("/")
+}
+
+/// The decompressed file at `relative`, if it is embedded.
+pub fn read(relative: &Path) -> Option<crate::Result<&'static [u8]>> {
+    let key = key(relative);
+    let i = ASSETS
+        .binary_search_by(|(path, _)| (*path).cmp(&key))
+        .ok()?;
+
+    let decompressed = &DECOMPRESSED[i];
+    if let Some(content) = decompressed.get() {
+        return Some(Ok(content));
+    }
+
+    Some(match miniz_oxide::inflate::decompress_to_vec(ASSETS[i].1) {
+        Ok(content) => Ok(decompressed.get_or_init(|| content)),
+        Err(err) => Err(crate::Error::Generic(
+            format!("Failed to decompress {key}: {err}").into(),
+        )),
+    })
+}
+
+/// The files and directories in the embedded directory `relative`, sorted by name. Empty if
+/// there is no such directory.
+pub fn read_dir(relative: &Path) -> Vec<Entry> {
+    let prefix = format!("{}/", key(relative));
+    let start = ASSETS.partition_point(|(path, _)| *path < prefix.as_str());
+
+    let mut entries: Vec<Entry> = Vec::new();
+    for (path, _) in ASSETS[start..]
+        .iter()
+        .take_while(|(path, _)| path.starts_with(&prefix))
+    {
+        let rest = &path[prefix.len()..];
+        let entry = match rest.split_once('/') {
+            Some((dir, _)) => Entry {
+                name: dir.to_string(),
+                is_dir: true,
+            },
+            None => Entry {
+                name: rest.to_string(),
+                is_dir: false,
+            },
+        };
+        if !entries.contains(&entry) {
+            entries.push(entry);
+        }
+    }
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embedded() {
+        let theme = read(Path::new("themes/dots.json")).unwrap().unwrap();
+        assert_eq!(theme, std::fs::read("data/themes/dots.json").unwrap());
+        let i = ASSETS
+            .iter()
+            .position(|(path, _)| *path == "themes/dots.json")
+            .unwrap();
+        assert_eq!(DECOMPRESSED[i].get().map(Vec::as_slice), Some(theme));
+        assert_eq!(read(Path::new("themes/dots.json")).unwrap().unwrap(), theme);
+        assert!(read(Path::new("themes/missing.json")).is_none());
+
+        let sound = read_dir(Path::new("sound"));
+        assert!(sound.contains(&Entry {
+            name: "click1".to_string(),
+            is_dir: true,
+        }));
+        assert!(
+            read_dir(Path::new("sound/click1"))
+                .iter()
+                .all(|entry| !entry.is_dir)
+        );
+        assert!(read_dir(Path::new("missing")).is_empty());
+    }
+}
@@ -0,0 +1,170 @@
+//! Sound effects from the `sound` data directory: a click for every key, an error sound for
+//! mistakes and a warning shortly before a time test ends. Sounds are played through an
+//! [`AudioSink`], so they can be dropped when there is no audio output or recorded in tests.
+
+pub mod sink;
+pub mod wav;
+
+pub use sink::{AudioSink, CommandSink, NullSink, RecordingSink};
+pub use wav::Sound;
+
+use rand::seq::IndexedRandom;
+
+use std::path::{Path, PathBuf};
+
+const SOUND_DIR: &str = "sound";
+const TIME_WARNING_FILE: &str = "timeWarning.wav";
+
+/// Names of the sound sets in `sound` that start with `prefix`, like `click` or `error`.
+pub fn sets(prefix: &str) -> crate::Result<Vec<String>> {
+    let mut names: Vec<String> = crate::dirs::read_dir(SOUND_DIR)?
+        .into_iter()
+        .filter(|entry| entry.is_dir)
+        .map(|entry| entry.name)
+        .filter(|name| name.starts_with(prefix))
+        .collect();
+    // Sorted by number, so that click14 comes after click7.
+    names.sort_by_key(|name| (name.len(), name.clone()));
+
+    Ok(names)
+}
+
+/// Every variant of the sound set `name`.
+fn load_set(name: &str) -> crate::Result<Vec<Sound>> {
+    let dir = Path::new(SOUND_DIR).join(name);
+    let paths: Vec<PathBuf> = crate::dirs::read_dir(&dir)
+        .map_err(|err| crate::Error::Generic(format!("Unknown sound set {name}: {err}").into()))?
+        .into_iter()
+        .filter(|entry| entry.name.ends_with(".wav"))
+        .map(|entry| dir.join(entry.name))
+        .collect();
+
+    paths.iter().map(|path| Sound::load(path)).collect()
+}
+
+/// The sounds chosen in the config and the sink they are played on.
+pub struct Audio {
+    sink: Box<dyn AudioSink>,
+    volume: f32,
+    click_set: Option<String>,
+    clicks: Vec<Sound>,
+    error_set: Option<String>,
+    errors: Vec<Sound>,
+    /// Seconds before the end of a time test the warning is played at.
+    time_warning: Option<u64>,
+    time_warning_sound: Option<Sound>,
+    /// Whether the warning was played in the current test.
+    warned: bool,
+}
+
+impl Audio {
+    pub fn new(sink: Box<dyn AudioSink>) -> Self {
+        Self {
+            sink,
+            volume: 0.0,
+            click_set: None,
+            clicks: Vec::new(),
+            error_set: None,
+            errors: Vec::new(),
+            time_warning: None,
+            time_warning_sound: None,
+            warned: false,
+        }
+    }
+
+    /// Plays on the best sink of the system.
+    pub fn system() -> Self {
+        Self::new(sink::system())
+    }
+
+    /// Loads the sounds chosen in `config` that changed and prepares for a new test.
+    pub fn configure(&mut self, config: &crate::Config) -> crate::Result<()> {
+        self.volume = config.volume.clamp(0.0, 1.0);
+        if self.click_set != config.click_sound {
+            self.clicks = match &config.click_sound {
+                Some(name) => load_set(name)?,
+                None => Vec::new(),
+            };
+            self.click_set = config.click_sound.clone();
+        }
+        if self.error_set != config.error_sound {
+            self.errors = match &config.error_sound {
+                Some(name) => load_set(name)?,
+                None => Vec::new(),
+            };
+            self.error_set = config.error_sound.clone();
+        }
+        self.time_warning = config.time_warning;
+        if self.time_warning.is_some() && self.time_warning_sound.is_none() {
+            let path = Path::new(SOUND_DIR).join(TIME_WARNING_FILE);
+            self.time_warning_sound = Some(Sound::load(&path)?);
+        }
+        self.warned = false;
+
+        Ok(())
+    }
+
+    /// Plays a random variant of the click set.
+    pub fn click(&mut self) {
+        if let Some(sound) = self.clicks.choose(&mut rand::rng()) {
+            self.sink.play(sound, self.volume);
+        }
+    }
+
+    /// Plays a random variant of the error set, or a click if there is no error set.
+    pub fn error(&mut self) {
+        match self.errors.choose(&mut rand::rng()) {
+            Some(sound) => self.sink.play(sound, self.volume),
+            None => self.click(),
+        }
+    }
+
+    /// Plays the time warning once per test, as soon as `seconds_left` reaches the configured
+    /// time.
+    pub fn time_left(&mut self, seconds_left: f64) {
+        if let (Some(warning), Some(sound)) = (self.time_warning, &self.time_warning_sound)
+            && !self.warned
+            && seconds_left <= warning as f64
+        {
+            self.warned = true;
+            self.sink.play(sound, self.volume);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn play() {
+        let sink = RecordingSink::default();
+        let mut audio = Audio::new(Box::new(sink.clone()));
+        audio.click();
+        assert!(sink.played().is_empty());
+
+        let config = crate::Config {
+            click_sound: Some("click2".to_string()),
+            time_warning: Some(3),
+            volume: 0.25,
+            ..Default::default()
+        };
+        audio.configure(&config).unwrap();
+        audio.click();
+        audio.error();
+        audio.time_left(5.0);
+        audio.time_left(2.5);
+        audio.time_left(1.0);
+
+        let played = sink.played();
+        assert_eq!(played.len(), 3);
+        assert!(played[0].0.starts_with("click2_"));
+        assert!(played[1].0.starts_with("click2_"));
+        assert_eq!(played[2], ("timeWarning".to_string(), 0.25));
+
+        assert_eq!(
+            sets("error").unwrap(),
+            ["error1", "error2", "error3", "error4"]
+        );
+    }
+}
@@ -0,0 +1,102 @@
+//! Where sounds are played.
+
+use super::Sound;
+
+use std::cell::RefCell;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::rc::Rc;
+use std::thread;
+
+/// Plays sounds without blocking the caller.
+pub trait AudioSink {
+    /// Starts playing `sound` with every sample multiplied by `volume`, which goes from 0 to 1.
+    fn play(&mut self, sound: &Sound, volume: f32);
+}
+
+/// Drops every sound, for when no audio output is available.
+pub struct NullSink;
+
+impl AudioSink for NullSink {
+    fn play(&mut self, _sound: &Sound, _volume: f32) {}
+}
+
+/// Remembers the name and volume of every played sound. Clones share what was played, so a
+/// clone can be kept to look at the sounds after the sink was handed over.
+#[derive(Clone, Default)]
+pub struct RecordingSink {
+    played: Rc<RefCell<Vec<(String, f32)>>>,
+}
+
+impl RecordingSink {
+    pub fn played(&self) -> Vec<(String, f32)> {
+        self.played.borrow().clone()
+    }
+}
+
+impl AudioSink for RecordingSink {
+    fn play(&mut self, sound: &Sound, volume: f32) {
+        self.played.borrow_mut().push((sound.name.clone(), volume));
+    }
+}
+
+/// Players that read a WAVE file from their standard input, with their arguments.
+const PLAYERS: [(&str, &[&str]); 3] = [("pw-play", &["-"]), ("paplay", &[]), ("aplay", &["-q"])];
+
+/// Plays sounds by piping them into a command line player of the system's sound server.
+///
+/// Every sound starts a new player process and a thread that waits for it, so a click sound
+/// costs a process and a thread per keypress. The players can't be kept running, since they
+/// only play a single file from their standard input.
+pub struct CommandSink {
+    program: &'static str,
+    args: &'static [&'static str],
+}
+
+impl CommandSink {
+    /// The first player that can be started, if any.
+    pub fn detect() -> Option<Self> {
+        PLAYERS.iter().find_map(|&(program, args)| {
+            let status = Command::new("which")
+                .arg(program)
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status();
+            status
+                .is_ok_and(|status| status.success())
+                .then_some(Self { program, args })
+        })
+    }
+}
+
+impl AudioSink for CommandSink {
+    fn play(&mut self, sound: &Sound, volume: f32) {
+        let child = Command::new(self.program)
+            .args(self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        let Ok(mut child) = child else {
+            return;
+        };
+
+        // Sounds are short, but the player is waited for off the main thread so that it
+        // doesn't stay around as a zombie process.
+        let wav = sound.encode(volume);
+        thread::spawn(move || {
+            if let Some(mut stdin) = child.stdin.take() {
+                let _ = stdin.write_all(&wav);
+            }
+            let _ = child.wait();
+        });
+    }
+}
+
+/// The best sink that is available on this system.
+pub fn system() -> Box<dyn AudioSink> {
+    match CommandSink::detect() {
+        Some(sink) => Box::new(sink),
+        None => Box::new(NullSink),
+    }
+}
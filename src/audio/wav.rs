@@ -0,0 +1,151 @@
+//! Reading and writing RIFF WAVE files with PCM or float samples.
+
+use std::path::Path;
+
+const FORMAT_PCM: u16 = 1;
+const FORMAT_FLOAT: u16 = 3;
+const FORMAT_EXTENSIBLE: u16 = 0xfffe;
+
+/// Decoded audio with interleaved samples between -1 and 1.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sound {
+    /// Name of the file without extension, like `click1_2`.
+    pub name: String,
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub samples: Vec<f32>,
+}
+
+impl Sound {
+    /// Loads the file at `path`, relative to the data directories.
+    pub fn load(path: &Path) -> crate::Result<Self> {
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        Self::decode(name, &crate::dirs::read(path)?).map_err(|err| {
+            crate::Error::Generic(format!("Failed to decode {}: {err}", path.display()).into())
+        })
+    }
+
+    /// Decodes 8, 16, 24 or 32 bit PCM or 32 bit float samples.
+    pub fn decode(name: String, bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() < 12 || &bytes[..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+            return Err("not a WAVE file".to_string());
+        }
+
+        let mut format = None;
+        let mut data = None;
+        let mut rest = &bytes[12..];
+        while rest.len() >= 8 {
+            let id = &rest[..4];
+            let size = u32::from_le_bytes([rest[4], rest[5], rest[6], rest[7]]) as usize;
+            let chunk = rest.get(8..8 + size).unwrap_or(&rest[8..]);
+            match id {
+                b"fmt " => format = Some(chunk),
+                b"data" => data = Some(chunk),
+                _ => (),
+            }
+            // Chunks are padded to an even size.
+            rest = rest.get(8 + size + size % 2..).unwrap_or_default();
+        }
+
+        let format = format
+            .filter(|f| f.len() >= 16)
+            .ok_or("missing format chunk")?;
+        let data = data.ok_or("missing data chunk")?;
+        let u16_at = |i: usize| u16::from_le_bytes([format[i], format[i + 1]]);
+
+        let mut tag = u16_at(0);
+        let channels = u16_at(2);
+        let sample_rate = u32::from_le_bytes([format[4], format[5], format[6], format[7]]);
+        let bits = u16_at(14);
+        if tag == FORMAT_EXTENSIBLE && format.len() >= 26 {
+            // The actual format is the start of the sub format GUID.
+            tag = u16_at(24);
+        }
+        if channels == 0 {
+            return Err("no channels".to_string());
+        }
+
+        let samples = match (tag, bits) {
+            (FORMAT_PCM, 8) => data.iter().map(|b| (*b as f32 - 128.0) / 128.0).collect(),
+            (FORMAT_PCM, 16) => data
+                .chunks_exact(2)
+                .map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0)
+                .collect(),
+            (FORMAT_PCM, 24) => data
+                .chunks_exact(3)
+                .map(|b| i32::from_le_bytes([0, b[0], b[1], b[2]]) as f32 / 2_147_483_648.0)
+                .collect(),
+            (FORMAT_PCM, 32) => data
+                .chunks_exact(4)
+                .map(|b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f32 / 2_147_483_648.0)
+                .collect(),
+            (FORMAT_FLOAT, 32) => data
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect(),
+            _ => return Err(format!("unsupported format {tag} with {bits} bits")),
+        };
+
+        Ok(Self {
+            name,
+            sample_rate,
+            channels,
+            samples,
+        })
+    }
+
+    /// Encodes the sound as 16 bit PCM with every sample multiplied by `volume`.
+    pub fn encode(&self, volume: f32) -> Vec<u8> {
+        let data_size = self.samples.len() as u32 * 2;
+        let block_align = self.channels * 2;
+
+        let mut bytes = Vec::with_capacity(44 + data_size as usize);
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data_size).to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&FORMAT_PCM.to_le_bytes());
+        bytes.extend_from_slice(&self.channels.to_le_bytes());
+        bytes.extend_from_slice(&self.sample_rate.to_le_bytes());
+        bytes.extend_from_slice(&(self.sample_rate * block_align as u32).to_le_bytes());
+        bytes.extend_from_slice(&block_align.to_le_bytes());
+        bytes.extend_from_slice(&16u16.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&data_size.to_le_bytes());
+        for sample in &self.samples {
+            let sample = (sample * volume).clamp(-1.0, 1.0) * i16::MAX as f32;
+            bytes.extend_from_slice(&(sample as i16).to_le_bytes());
+        }
+
+        bytes
+    }
+
+    pub fn duration(&self) -> f64 {
+        self.samples.len() as f64 / self.channels as f64 / self.sample_rate as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let sound = Sound::load(Path::new("sound/click2/click2_1.wav")).unwrap();
+        assert_eq!(sound.name, "click2_1");
+        assert_eq!((sound.channels, sound.sample_rate), (1, 44100));
+        assert!(sound.duration() > 0.0);
+
+        let decoded = Sound::decode(sound.name.clone(), &sound.encode(0.5)).unwrap();
+        assert_eq!(decoded.samples.len(), sound.samples.len());
+        for (a, b) in decoded.samples.iter().zip(&sound.samples) {
+            assert!((a - b * 0.5).abs() < 0.001);
+        }
+
+        assert!(Sound::decode(String::new(), b"RIFF\0\0\0\0WAVE").is_err());
+    }
+}
@@ -0,0 +1,270 @@
+//! Where files are read from and written to.
+//!
+//! Every directory can be set on the command line or with an environment variable, which wins
+//! over the XDG base directories, which win over their standard fallbacks:
+//!
+//! - config: `--config-dir`, `$TYP_CONFIG_DIR`, `$XDG_CONFIG_HOME/typ`, `~/.config/typ`
+//! - state, the history, replays and everything else that is recorded:
+//!   `--state-dir`, `$TYP_STATE_DIR`, `$XDG_STATE_HOME/typ`, `~/.local/state/typ`
+//! - data, the user's own themes, languages, layouts and so on:
+//!   `--data-dir`, `$TYP_DATA_DIR`, `$XDG_DATA_HOME/typ`, `~/.local/share/typ`
+//! - assets, the bundled data: `--assets-dir`, `$TYP_ASSETS_DIR`, or else every `typ` directory
+//!   in `$XDG_DATA_DIRS` (`/usr/local/share:/usr/share`).
+//!
+//! Data files are looked up in the data directory first, then in the asset directories and
+//! last in the assets embedded in the binary, so a user's files hide bundled files with the
+//! same name. Without a home directory, config and state fall back to the relative
+//! `config` and `user_data` directories.
+
+use crate::assets;
+
+use serde::de::DeserializeOwned;
+
+use std::borrow::Cow;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+const APP_NAME: &str = "typ";
+/// Stands in for the directory of embedded assets in paths shown to the user.
+const EMBEDDED_DIR: &str = "<embedded>";
+
+static DIRS: OnceLock<Dirs> = OnceLock::new();
+
+/// Directories given on the command line.
+#[derive(Clone, Debug, Default)]
+pub struct Overrides {
+    pub config: Option<PathBuf>,
+    pub state: Option<PathBuf>,
+    pub data: Option<PathBuf>,
+    pub assets: Option<PathBuf>,
+}
+
+#[derive(Debug)]
+struct Dirs {
+    config: PathBuf,
+    state: PathBuf,
+    /// The user's data directory, followed by the asset directories.
+    data: Vec<PathBuf>,
+}
+
+impl Dirs {
+    fn resolve(overrides: Overrides) -> Self {
+        let home = env::var_os("HOME")
+            .filter(|home| !home.is_empty())
+            .map(PathBuf::from);
+        let dir = |flag: Option<PathBuf>, var: &str, xdg: &str, fallback: &str, relative: &str| {
+            flag.or_else(|| env_path(var))
+                .or_else(|| env_path(xdg).map(|dir| dir.join(APP_NAME)))
+                .or_else(|| home.as_ref().map(|home| home.join(fallback).join(APP_NAME)))
+                .unwrap_or_else(|| PathBuf::from(relative))
+        };
+
+        let config = dir(
+            overrides.config,
+            "TYP_CONFIG_DIR",
+            "XDG_CONFIG_HOME",
+            ".config",
+            "config",
+        );
+        let state = dir(
+            overrides.state,
+            "TYP_STATE_DIR",
+            "XDG_STATE_HOME",
+            ".local/state",
+            "user_data",
+        );
+        let mut data = vec![dir(
+            overrides.data,
+            "TYP_DATA_DIR",
+            "XDG_DATA_HOME",
+            ".local/share",
+            "user_data/data",
+        )];
+
+        match overrides.assets.or_else(|| env_path("TYP_ASSETS_DIR")) {
+            Some(assets) => data.push(assets),
+            None => {
+                let system = env::var("XDG_DATA_DIRS")
+                    .ok()
+                    .filter(|dirs| !dirs.is_empty())
+                    .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+                data.extend(
+                    env::split_paths(&system)
+                        .filter(|dir| dir.is_absolute())
+                        .map(|dir| dir.join(APP_NAME)),
+                );
+            }
+        }
+
+        Self {
+            config,
+            state,
+            data,
+        }
+    }
+}
+
+/// An absolute path from the environment variable `var`. Relative paths are ignored, as the
+/// XDG spec asks.
+fn env_path(var: &str) -> Option<PathBuf> {
+    env::var_os(var)
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+}
+
+fn get() -> &'static Dirs {
+    DIRS.get_or_init(|| Dirs::resolve(Overrides::default()))
+}
+
+/// Resolves the directories with `overrides`. Has to be called before any file is accessed,
+/// later calls do nothing.
+pub fn init(overrides: Overrides) {
+    let _ = DIRS.set(Dirs::resolve(overrides));
+}
+
+pub fn config() -> &'static Path {
+    &get().config
+}
+
+pub fn state() -> &'static Path {
+    &get().state
+}
+
+/// The data directories, in the order they are searched before the embedded assets.
+pub fn data() -> &'static [PathBuf] {
+    &get().data
+}
+
+/// The user's own data directory, the first of [`data`].
+pub fn user_data() -> &'static Path {
+    &get().data[0]
+}
+
+/// A file or directory in a data directory.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub name: String,
+    pub is_dir: bool,
+}
+
+/// The content of the first `relative` file in the data directories or the embedded assets.
+/// Embedded files are borrowed, so they aren't copied on every read.
+pub fn read(relative: impl AsRef<Path>) -> crate::Result<Cow<'static, [u8]>> {
+    read_in(data(), relative.as_ref()).map(|(content, _)| content)
+}
+
+/// The first `relative` file like [`read`], parsed as JSON.
+pub fn read_json<T: DeserializeOwned>(relative: impl AsRef<Path>) -> crate::Result<T> {
+    let (content, path) = read_in(data(), relative.as_ref())?;
+    serde_json::from_slice(&content).map_err(|err| crate::Error::parsing_file(path, err))
+}
+
+/// The content of the file and the path it was read from.
+fn read_in(dirs: &[PathBuf], relative: &Path) -> crate::Result<(Cow<'static, [u8]>, PathBuf)> {
+    let mut searched = Vec::new();
+    for dir in dirs {
+        let path = dir.join(relative);
+        match fs::read(&path) {
+            Ok(content) => return Ok((Cow::Owned(content), path)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => searched.push(path),
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    match assets::read(relative) {
+        Some(content) => Ok((Cow::Borrowed(content?), Path::new(EMBEDDED_DIR).join(relative))),
+        None => Err(crate::Error::NotFound {
+            path: relative.to_path_buf(),
+            searched,
+        }),
+    }
+}
+
+/// Everything in the `relative` directory of all data directories and the embedded assets,
+/// sorted by name. An entry hides entries with the same name in the directories searched after
+/// it.
+pub fn read_dir(relative: impl AsRef<Path>) -> crate::Result<Vec<Entry>> {
+    read_dir_in(data(), relative.as_ref())
+}
+
+fn read_dir_in(dirs: &[PathBuf], relative: &Path) -> crate::Result<Vec<Entry>> {
+    let mut entries = Vec::new();
+    let mut searched = Vec::new();
+
+    for dir in dirs {
+        let dir = dir.join(relative);
+        let dir_entries = match fs::read_dir(&dir) {
+            Ok(dir_entries) => dir_entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                searched.push(dir);
+                continue;
+            }
+            Err(err) => return Err(err.into()),
+        };
+        for entry in dir_entries {
+            let entry = entry?;
+            entries.push(Entry {
+                name: entry.file_name().to_string_lossy().into_owned(),
+                is_dir: entry.file_type()?.is_dir(),
+            });
+        }
+        searched.push(dir);
+    }
+    entries.extend(assets::read_dir(relative));
+
+    if entries.is_empty() && !searched.iter().any(|dir| dir.exists()) {
+        return Err(crate::Error::NotFound {
+            path: relative.to_path_buf(),
+            searched,
+        });
+    }
+    // The sort is stable, so the first entry with a name is kept.
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+    entries.dedup_by(|later, first| later.name == first.name);
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layers() {
+        let user = env::temp_dir().join(format!("typ-dirs-{}", std::process::id()));
+        let themes = user.join("themes");
+        fs::create_dir_all(&themes).unwrap();
+        fs::write(themes.join("custom.json"), "{}").unwrap();
+        fs::write(themes.join("dots.json"), "{}").unwrap();
+
+        let dirs = Dirs::resolve(Overrides {
+            data: Some(user.clone()),
+            assets: Some(user.join("assets")),
+            ..Default::default()
+        });
+        let themes_dir = Path::new("themes");
+        let names: Vec<String> = read_dir_in(&dirs.data, themes_dir)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.name)
+            .collect();
+        assert!(names.contains(&"custom.json".to_string()));
+        assert!(names.contains(&"8008.json".to_string()));
+        assert_eq!(names.iter().filter(|name| *name == "dots.json").count(), 1);
+
+        let read = |name: &str| read_in(&dirs.data, &themes_dir.join(name)).map(|(c, _)| c);
+        assert_eq!(*read("dots.json").unwrap(), *b"{}");
+        let embedded = read("8008.json").unwrap();
+        assert!(matches!(embedded, Cow::Borrowed(_)));
+        assert_eq!(*embedded, fs::read("data/themes/8008.json").unwrap());
+        let Err(crate::Error::NotFound { searched, .. }) = read("missing.json") else {
+            panic!("missing file was found");
+        };
+        assert_eq!(searched.len(), 2);
+
+        fs::remove_dir_all(user).unwrap();
+    }
+}
@@ -2,6 +2,7 @@ use crate::monkeytype::Language;
 use crate::typing::QuoteLength;
 
 use std::fmt;
+use std::path::PathBuf;
 
 pub type BoxError = Box<dyn std::error::Error>;
 pub type Result<T> = std::result::Result<T, Error>;
@@ -10,11 +11,44 @@ pub enum Error {
     NoWordsForLanguage(Language),
     NoQuoteWithLengths(Vec<QuoteLength>),
     NoQuotesForLanguage(Language),
+    UnknownLanguage(String),
     Generic(BoxError),
-    ParsingConfig(BoxError),
+    /// Invalid JSON in a config or data file, at a 1-based line and column.
+    ParsingFile {
+        path: PathBuf,
+        line: usize,
+        column: usize,
+        err: BoxError,
+    },
+    ParsingHistory {
+        path: PathBuf,
+        line: usize,
+        err: BoxError,
+    },
+    /// A data file that isn't in any of the data directories.
+    NotFound {
+        path: PathBuf,
+        searched: Vec<PathBuf>,
+    },
 }
 
 impl Error {
+    /// Error for `err` in the JSON file at `path`.
+    pub fn parsing_file(path: impl Into<PathBuf>, err: serde_json::Error) -> Self {
+        let (line, column) = (err.line(), err.column());
+        // serde_json appends the position to its message.
+        let message = err.to_string();
+        let suffix = format!(" at line {line} column {column}");
+        let message = message.strip_suffix(&suffix).unwrap_or(&message);
+
+        Self::ParsingFile {
+            path: path.into(),
+            line,
+            column,
+            err: message.into(),
+        }
+    }
+
     pub fn as_error(self) -> BoxError {
         match self {
             Self::Generic(err) => err,
@@ -33,8 +67,22 @@ impl Error {
             Self::NoQuotesForLanguage(language) => {
                 format!("There are no quotes for the language: {language}")
             }
+            Self::UnknownLanguage(name) => format!("Unknown language: {name}"),
             Self::Generic(err) => err.to_string(),
-            Self::ParsingConfig(err) => format!("Failed to parse config: {err}"),
+            Self::ParsingFile {
+                path,
+                line,
+                column,
+                err,
+            } => format!("Failed to parse {}:{line}:{column}: {err}", path.display()),
+            Self::ParsingHistory { path, line, err } => {
+                format!("Failed to parse history {}:{line}: {err}", path.display())
+            }
+            Self::NotFound { path, searched } => {
+                let searched: Vec<String> =
+                    searched.iter().map(|path| path.display().to_string()).collect();
+                format!("Could not find {}, searched: {}", path.display(), searched.join(", "))
+            }
         }
     }
 }
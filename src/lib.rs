@@ -1,4 +1,7 @@
 pub mod app;
+mod assets;
+pub mod audio;
+pub mod dirs;
 pub mod monkeytype;
 pub mod user;
 pub mod typing;
@@ -6,10 +9,8 @@ pub mod error;
 
 pub use app::App;
 pub use error::{Error, Result};
-pub use user::{Config, config::style::{Theme, Style}};
+pub use user::{Config, ConfigOverrides, config::style::{Theme, Style}};
 
-pub const DATA_DIR: &str = "data";
-pub const CONFIG_DIR: &str = "config";
 pub const CONFIG_FILE: &str = "config.json";
 
 pub const CHARS_PER_WORD: f32 = 5.;
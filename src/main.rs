@@ -1,11 +1,427 @@
+use typ::audio::Sound;
+use typ::dirs::Overrides;
+use typ::monkeytype::layout::analysis::{self, Analysis, Corpus};
+use typ::monkeytype::{Challenge, KeyboardLayout, Language, Quotes, Words};
+use typ::typing::QuoteLength;
+use typ::user::Stats;
+use typ::user::stats::filter::format_timestamp;
+use typ::user::stats::{DateRange, ExportFormat, Filter, ModeKind};
+use typ::{ConfigOverrides, Theme};
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+const USAGE: &str = "\
+Usage: typ [options]
+       typ <command> [arguments]
+
+Options start the first test with other settings than the saved ones, without changing them:
+  --time <seconds>         time mode
+  --words <count>          words mode
+  --quote <length>         quote mode with all, short, medium, long or thicc quotes
+  --language <language>    language of the words and quotes
+  --theme <theme>          theme
+  --punctuation            add punctuation
+  --numbers                add numbers
+  --file <path>            type the words of a file
+
+Commands:
+  languages                list the languages
+  themes                   list the themes
+  layouts                  list the keyboard layouts
+  stats                    print a summary of the history
+  pbs                      print the personal bests
+  export <path> [filters]  export the history as CSV or JSON, see `--export`
+  import <path>            import a monkeytype CSV export
+  analyze <layout>...      compare keyboard layouts
+  validate                 check that every data file can be loaded
+  help                     print this help
+
+Directories:
+  --config-dir <path>, --state-dir <path>, --data-dir <path>, --assets-dir <path>
+";
 
 fn main() -> typ::Result<()> {
-    let mut terminal = ratatui::init();
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    typ::dirs::init(dir_overrides(&mut args)?);
 
-    let mut app = typ::App::new()?;
+    match args.first().map(String::as_str) {
+        Some("import") => return import(&args[1..]),
+        Some("export" | "--export") => return export(&args[1..]),
+        Some("analyze") => return analyze(&args[1..]),
+        Some("languages") => {
+            Language::ALL.iter().for_each(|language| println!("{language}"));
+            return Ok(());
+        }
+        Some("themes") => {
+            Theme::names()?.iter().for_each(|name| println!("{name}"));
+            return Ok(());
+        }
+        Some("layouts") => {
+            KeyboardLayout::names()?.iter().for_each(|name| println!("{name}"));
+            return Ok(());
+        }
+        Some("stats") => return print_stats(),
+        Some("pbs") => return print_personal_bests(),
+        Some("validate") => return validate(),
+        Some("help" | "--help" | "-h") => {
+            print!("{USAGE}");
+            return Ok(());
+        }
+        _ => (),
+    }
+    let overrides = config_overrides(&args)?;
+    // Built before the terminal is set up, so that nothing can fail with the terminal in raw
+    // mode.
+    let mut app = typ::App::with_overrides(overrides);
+
+    let mut terminal = ratatui::init();
 
     let result = app.run(&mut terminal);
 
     ratatui::restore();
     result
 }
+
+/// Takes `--config-dir`, `--state-dir`, `--data-dir` and `--assets-dir` with their paths out of
+/// `args`, wherever they are.
+fn dir_overrides(args: &mut Vec<String>) -> typ::Result<Overrides> {
+    let mut overrides = Overrides::default();
+    let mut i = 0;
+    while i < args.len() {
+        let dir = match args[i].as_str() {
+            "--config-dir" => &mut overrides.config,
+            "--state-dir" => &mut overrides.state,
+            "--data-dir" => &mut overrides.data,
+            "--assets-dir" => &mut overrides.assets,
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+        if i + 1 == args.len() {
+            return Err(typ::Error::Generic(format!("{} needs a path", args[i]).into()));
+        }
+        *dir = Some(PathBuf::from(args.remove(i + 1)));
+        args.remove(i);
+    }
+
+    Ok(overrides)
+}
+
+/// The settings of the options in `args`.
+fn config_overrides(args: &[String]) -> typ::Result<ConfigOverrides> {
+    let invalid = |msg: String| typ::Error::Generic(msg.into());
+    let mut overrides = ConfigOverrides::default();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| invalid(format!("{arg} needs a value")))
+        };
+        let mut number = || {
+            let value = value()?;
+            value
+                .parse::<usize>()
+                .ok()
+                .filter(|number| *number > 0)
+                .ok_or_else(|| invalid(format!("{arg} needs a positive number, not {value}")))
+        };
+        match arg.as_str() {
+            "--time" => {
+                overrides.length = Some(number()?);
+                overrides.mode = Some(ModeKind::Time);
+            }
+            "--words" => {
+                overrides.length = Some(number()?);
+                overrides.mode = Some(ModeKind::Words);
+            }
+            "--quote" => {
+                let lengths = value()?.split(',').map(QuoteLength::from_str);
+                overrides.quote_lengths = Some(lengths.collect::<typ::Result<_>>()?);
+                overrides.mode = Some(ModeKind::Quote);
+            }
+            "--language" => overrides.language = Some(Language::from_str(value()?)?),
+            "--theme" => overrides.theme = Some(Theme::load(value()?.clone())?),
+            "--punctuation" => overrides.punctuation = Some(true),
+            "--numbers" => overrides.numbers = Some(true),
+            "--file" => {
+                let path = value()?;
+                let text = fs::read_to_string(path)
+                    .map_err(|err| invalid(format!("Failed to read {path}: {err}")))?;
+                let words: Vec<String> = text.split_whitespace().map(String::from).collect();
+                if words.is_empty() {
+                    return Err(invalid(format!("{path} has no words")));
+                }
+                overrides.text = Some(words);
+            }
+            _ => return Err(invalid(format!("Unknown option: {arg}, see `typ help`"))),
+        }
+    }
+
+    Ok(overrides)
+}
+
+/// Prints the number of tests, the time spent typing and the average and best results.
+fn print_stats() -> typ::Result<()> {
+    let stats = Stats::load()?;
+    let completed: Vec<_> = stats.completed().collect();
+    let seconds: f64 = stats.history().iter().map(|result| result.duration).sum();
+
+    println!(
+        "Tests: {} started, {} completed",
+        stats.history().len(),
+        completed.len()
+    );
+    println!(
+        "Time typing: {}h {:02}m {:02}s",
+        seconds as u64 / 3600,
+        seconds as u64 % 3600 / 60,
+        seconds as u64 % 60
+    );
+    if completed.is_empty() {
+        return Ok(());
+    }
+
+    let average = |value: fn(&typ::user::stats::TestResult) -> f32| {
+        completed.iter().map(|result| value(result)).sum::<f32>() / completed.len() as f32
+    };
+    let best = completed
+        .iter()
+        .max_by(|a, b| a.wpm.total_cmp(&b.wpm))
+        .expect("completed isn't empty");
+    println!(
+        "Average: {:.2} wpm, {:.2} raw, {:.2}% accuracy, {:.2}% consistency",
+        average(|result| result.wpm),
+        average(|result| result.raw_wpm),
+        average(|result| result.accuracy),
+        average(|result| result.consistency)
+    );
+    println!(
+        "Best: {:.2} wpm, {} {} {} on {}",
+        best.wpm,
+        best.mode,
+        best.length,
+        best.language,
+        format_timestamp(best.timestamp)
+    );
+
+    Ok(())
+}
+
+/// Prints every personal best, sorted by mode, length and language.
+fn print_personal_bests() -> typ::Result<()> {
+    let stats = Stats::load()?;
+    let mut bests: Vec<_> = stats.personal_bests().iter().collect();
+    bests.sort_by_key(|pb| {
+        let key = pb.key;
+        (key.mode.to_string(), key.length, key.language.to_string(), key.punctuation, key.numbers)
+    });
+
+    for pb in bests {
+        let key = pb.key;
+        let mut options = Vec::new();
+        if key.punctuation {
+            options.push("punctuation".to_string());
+        }
+        if key.numbers {
+            options.push("numbers".to_string());
+        }
+        options.push(key.difficulty.to_string());
+        println!(
+            "{} {} {} ({}): {:.2} wpm, {:.2}% accuracy on {}",
+            key.mode,
+            key.length,
+            key.language,
+            options.join(", "),
+            pb.wpm,
+            pb.accuracy,
+            format_timestamp(pb.timestamp)
+        );
+    }
+
+    Ok(())
+}
+
+/// Loads every language, quote list, theme, layout, challenge and sound, and prints the ones
+/// that fail.
+fn validate() -> typ::Result<()> {
+    let mut checked = 0;
+    let mut failed = Vec::new();
+    let mut check = |name: String, result: typ::Result<()>| {
+        checked += 1;
+        if let Err(err) = result {
+            failed.push(format!("{name}: {err}"));
+        }
+    };
+
+    for dir in ["languages", "quotes"] {
+        for entry in typ::dirs::read_dir(dir)? {
+            let path = Path::new(dir).join(&entry.name);
+            let result = match dir {
+                "languages" => typ::dirs::read_json::<Words>(&path).map(|_| ()),
+                _ => typ::dirs::read_json::<Quotes>(&path).map(|_| ()),
+            };
+            check(path.display().to_string(), result);
+        }
+    }
+    for theme in Theme::names()? {
+        let result = Theme::load(theme.clone()).map(|_| ());
+        check(format!("themes/{theme}.json"), result);
+    }
+    for layout in KeyboardLayout::names()? {
+        let result = KeyboardLayout::load(&layout).map(|_| ());
+        check(format!("layouts/{layout}.json"), result);
+    }
+    match Challenge::all() {
+        Ok(challenges) => {
+            for challenge in challenges {
+                check(format!("challenge {}", challenge.name), challenge.mode().map(|_| ()));
+            }
+        }
+        Err(err) => check("challenges/_list.json".to_string(), Err(err)),
+    }
+    for entry in typ::dirs::read_dir("sound")? {
+        let path = Path::new("sound").join(&entry.name);
+        let paths = match entry.is_dir {
+            true => typ::dirs::read_dir(&path)?
+                .into_iter()
+                .map(|file| path.join(file.name))
+                .collect(),
+            false => vec![path],
+        };
+        for path in paths.iter().filter(|path| path.extension().is_some_and(|ext| ext == "wav")) {
+            check(path.display().to_string(), Sound::load(path).map(|_| ()));
+        }
+    }
+
+    for failure in &failed {
+        eprintln!("{failure}");
+    }
+    println!("{checked} files checked, {} failed", failed.len());
+    if !failed.is_empty() {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// `import <path>`
+fn import(args: &[String]) -> typ::Result<()> {
+    let [path] = args else {
+        return Err(typ::Error::Generic("Usage: typ import <path>".into()));
+    };
+
+    let mut stats = Stats::load()?;
+    let summary = stats.import_monkeytype_csv(Path::new(path))?;
+
+    println!(
+        "Imported {} results ({} duplicates, {} skipped)",
+        summary.imported,
+        summary.duplicates,
+        summary.skipped.len()
+    );
+    for (row, reason) in &summary.skipped {
+        eprintln!("row {row}: {reason}");
+    }
+
+    Ok(())
+}
+
+/// `--export <path> [--mode <mode>] [--language <language>] [--tag <tag>] [--range <range>]`
+///
+/// Files ending in `.json` are written as JSON, everything else as CSV.
+fn export(args: &[String]) -> typ::Result<()> {
+    let invalid = |msg: String| typ::Error::Generic(msg.into());
+    let Some((path, options)) = args.split_first() else {
+        return Err(invalid("--export needs a path".to_string()));
+    };
+
+    let mut filter = Filter::default();
+    for option in options.chunks(2) {
+        let [name, value] = option else {
+            return Err(invalid(format!("{} needs a value", option[0])));
+        };
+        match name.as_str() {
+            "--mode" => {
+                filter.mode = Some(match value.as_str() {
+                    "time" => ModeKind::Time,
+                    "words" => ModeKind::Words,
+                    "quote" => ModeKind::Quote,
+                    "custom" => ModeKind::Custom,
+                    _ => return Err(invalid(format!("Unknown mode: {value}"))),
+                })
+            }
+            "--language" => filter.language = Some(Language::from_str(value)?),
+            "--tag" => filter.tag = Some(value.clone()),
+            "--range" => {
+                filter.date_range = match value.as_str() {
+                    "all" => DateRange::All,
+                    "today" => DateRange::Today,
+                    "week" => DateRange::Week,
+                    "month" => DateRange::Month,
+                    "year" => DateRange::Year,
+                    _ => return Err(invalid(format!("Unknown range: {value}"))),
+                }
+            }
+            _ => return Err(invalid(format!("Unknown option: {name}"))),
+        }
+    }
+
+    let path = Path::new(path);
+    let count = Stats::load()?.export(&filter, path, ExportFormat::from_path(path))?;
+    println!("Exported {count} results to {}", path.display());
+
+    Ok(())
+}
+
+/// `analyze <layout>... [--language <language> | --quotes <language> | --history]`
+///
+/// Compares the layouts side by side. The corpus defaults to the english word list.
+fn analyze(args: &[String]) -> typ::Result<()> {
+    let invalid = |msg: String| typ::Error::Generic(msg.into());
+
+    let mut layouts = Vec::new();
+    let mut corpus = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut language = || match args.next() {
+            Some(value) => Language::from_str(value),
+            None => Err(invalid(format!("{arg} needs a value"))),
+        };
+        match arg.as_str() {
+            "--language" => corpus = Some(Corpus::from_language(&language()?)?),
+            "--quotes" => corpus = Some(Corpus::from_quotes(&language()?)?),
+            "--history" => {
+                let mut errors = Vec::new();
+                corpus = Some(Corpus::from_history(&mut errors)?);
+                for err in errors {
+                    eprintln!("{err}");
+                }
+            }
+            _ if arg.starts_with("--") => return Err(invalid(format!("Unknown option: {arg}"))),
+            name => layouts.push(KeyboardLayout::load(name)?),
+        }
+    }
+
+    if layouts.is_empty() {
+        return Err(invalid("analyze needs at least one layout".to_string()));
+    }
+    let corpus = match corpus {
+        Some(corpus) => corpus,
+        None => Corpus::from_language(&Language::English)?,
+    };
+    if corpus.is_empty() {
+        return Err(invalid("The corpus is empty".to_string()));
+    }
+
+    let analyses: Vec<Analysis> = layouts
+        .iter()
+        .map(|layout| Analysis::new(layout, &corpus))
+        .collect();
+    println!("{}", analysis::table(&analyses));
+
+    Ok(())
+}
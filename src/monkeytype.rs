@@ -1,7 +1,11 @@
+pub mod challenge;
+pub mod layout;
 pub mod quote;
 pub mod words;
 pub mod punctuation;
 
+pub use challenge::{Challenge, ChallengeKind};
+pub use layout::{Key, KeyPosition, KeyboardLayout, LayoutType};
 pub use quote::{Quote, QuoteLanguage, Quotes};
 pub use words::{Language, Words};
 
@@ -37,10 +41,9 @@ impl MonkeyType {
         }
     }
 
-    pub fn set_language(&mut self, language: Language) -> crate::Result<()> { 
-        self.quotes = Quotes::from_language(&language)?;
-        self.words = Words::from_language(&language)?;
-        self.language = language;
+    /// Switches to `language`. Nothing changes if its words or quotes can't be loaded.
+    pub fn set_language(&mut self, language: Language) -> crate::Result<()> {
+        *self = Self::new(language)?;
         Ok(())
     }
 }
@@ -0,0 +1,388 @@
+//! Challenges from `data/challenges/_list.json`: tests with fixed settings and requirements the
+//! result has to meet.
+
+use super::Language;
+use crate::typing::{Difficulty, Mode, Seconds, TextOrder, WordCount, funbox};
+use crate::user::stats::{ModeKind, TestResult};
+
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+const CHALLENGES_DIR: &str = "challenges";
+const LIST_FILE: &str = "_list.json";
+/// Layoutfluid challenges only mention in their message that every layout needs this WPM.
+const LAYOUTFLUID_MIN_WPM: f32 = 50.0;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ChallengeKind {
+    CustomTime {
+        seconds: usize,
+    },
+    CustomWords {
+        words: usize,
+    },
+    CustomText {
+        text: String,
+        order: TextOrder,
+        words: usize,
+    },
+    /// A text from a file next to the challenge list, typed from start to end.
+    Script {
+        file: String,
+        funbox: Vec<String>,
+    },
+    Funbox {
+        funbox: Vec<String>,
+        mode: ModeKind,
+        length: usize,
+        difficulty: Difficulty,
+    },
+    /// Any test, only the requirements matter.
+    Accuracy,
+}
+
+/// Bounds for a value of the result. `exact` is compared with the rounded value.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct Bounds {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub exact: Option<f64>,
+}
+
+impl Bounds {
+    pub fn contains(&self, value: f64) -> bool {
+        self.min.is_none_or(|min| value >= min)
+            && self.max.is_none_or(|max| value <= max)
+            && self.exact.is_none_or(|exact| value.round() == exact)
+    }
+}
+
+impl fmt::Display for Bounds {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut bounds = Vec::new();
+        if let Some(min) = self.min {
+            bounds.push(format!("at least {min}"));
+        }
+        if let Some(max) = self.max {
+            bounds.push(format!("at most {max}"));
+        }
+        if let Some(exact) = self.exact {
+            bounds.push(format!("exactly {exact}"));
+        }
+
+        write!(f, "{}", bounds.join(" and "))
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct FunboxRequirement {
+    pub exact: Vec<String>,
+}
+
+/// What the result of a challenge has to meet. AFK time isn't tracked, and the only config
+/// options that are checked are `language`, `punctuation` and `numbers`, because the other ones
+/// don't exist here.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct Requirements {
+    pub wpm: Option<Bounds>,
+    pub raw: Option<Bounds>,
+    pub acc: Option<Bounds>,
+    pub con: Option<Bounds>,
+    /// Duration in seconds.
+    pub time: Option<Bounds>,
+    pub afk: Option<Bounds>,
+    pub funbox: Option<FunboxRequirement>,
+    #[serde(default)]
+    pub config: HashMap<String, Value>,
+}
+
+impl Requirements {
+    /// Describes every requirement `result` doesn't meet.
+    pub fn failures(&self, result: &TestResult) -> Vec<String> {
+        let mut failures = Vec::new();
+
+        let values = [
+            ("wpm", &self.wpm, result.wpm as f64),
+            ("raw", &self.raw, result.raw_wpm as f64),
+            ("accuracy", &self.acc, result.accuracy as f64),
+            ("consistency", &self.con, result.consistency as f64),
+            ("time", &self.time, result.duration),
+        ];
+        for (label, bounds, value) in values {
+            if let Some(bounds) = bounds
+                && !bounds.contains(value)
+            {
+                failures.push(format!("{label} {value:.0} is not {bounds}"));
+            }
+        }
+
+        if let Some(funbox) = &self.funbox
+            && funbox.exact != result.funbox
+        {
+            failures.push(format!("funbox has to be {}", funbox.exact.join(", ")));
+        }
+
+        for (option, value) in &self.config {
+            let actual = match option.as_str() {
+                "language" => Value::from(result.language.to_string()),
+                "punctuation" => Value::from(result.punctuation),
+                "numbers" => Value::from(result.numbers),
+                _ => continue,
+            };
+            if actual != *value {
+                failures.push(format!("{option} has to be {value}"));
+            }
+        }
+
+        failures
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChallengeEntry {
+    name: String,
+    display: String,
+    #[serde(default)]
+    auto_role: bool,
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    parameters: Vec<Value>,
+    message: Option<String>,
+    #[serde(default)]
+    requirements: Requirements,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(try_from = "ChallengeEntry")]
+pub struct Challenge {
+    pub name: String,
+    pub display: String,
+    /// Whether monkeytype gives a Discord role for the challenge.
+    pub auto_role: bool,
+    pub kind: ChallengeKind,
+    /// Shown when the test ends, usually explains the requirements.
+    pub message: Option<String>,
+    pub requirements: Requirements,
+}
+
+impl TryFrom<ChallengeEntry> for Challenge {
+    type Error = String;
+
+    fn try_from(entry: ChallengeEntry) -> Result<Self, Self::Error> {
+        // Missing parameters are read as `null`, so optional ones can be left out.
+        let parameter = |index: usize| entry.parameters.get(index).cloned().unwrap_or_default();
+        fn parse<T: DeserializeOwned>(name: &str, value: Value) -> Result<T, String> {
+            serde_json::from_value(value).map_err(|err| format!("{name}: {err}"))
+        }
+        let name = entry.name.as_str();
+
+        let kind = match entry.kind.as_str() {
+            "customTime" => ChallengeKind::CustomTime {
+                seconds: parse(name, parameter(0))?,
+            },
+            "customWords" => ChallengeKind::CustomWords {
+                words: parse(name, parameter(0))?,
+            },
+            "customText" => {
+                let limit_mode: String = parse(name, parameter(3))?;
+                if limit_mode != "word" {
+                    return Err(format!("{name}: unsupported limit mode {limit_mode}"));
+                }
+                ChallengeKind::CustomText {
+                    text: parse(name, parameter(0))?,
+                    order: parse(name, parameter(1))?,
+                    words: parse(name, parameter(2))?,
+                }
+            }
+            "script" => ChallengeKind::Script {
+                file: parse(name, parameter(0))?,
+                funbox: parse::<Option<_>>(name, parameter(2))?.unwrap_or_default(),
+            },
+            "funbox" => ChallengeKind::Funbox {
+                funbox: parse(name, parameter(0))?,
+                mode: parse(name, parameter(1))?,
+                length: parse(name, parameter(2))?,
+                difficulty: parse::<Option<_>>(name, parameter(3))?.unwrap_or_default(),
+            },
+            "accuracy" => ChallengeKind::Accuracy,
+            kind => return Err(format!("{name}: unknown challenge type {kind}")),
+        };
+
+        Ok(Self {
+            name: entry.name,
+            display: entry.display,
+            auto_role: entry.auto_role,
+            kind,
+            message: entry.message,
+            requirements: entry.requirements,
+        })
+    }
+}
+
+impl Challenge {
+    /// The file `name` of the challenge data, relative to the data directories.
+    fn path(name: &str) -> PathBuf {
+        Path::new(CHALLENGES_DIR).join(name)
+    }
+
+    /// All challenges in the order of the list.
+    pub fn all() -> crate::Result<Vec<Self>> {
+        crate::dirs::read_json(Self::path(LIST_FILE))
+    }
+
+    pub fn load(name: &str) -> crate::Result<Self> {
+        Self::all()?
+            .into_iter()
+            .find(|challenge| challenge.name == name)
+            .ok_or_else(|| crate::Error::Generic(format!("Unknown challenge: {name}").into()))
+    }
+
+    pub fn funbox(&self) -> &[String] {
+        match &self.kind {
+            ChallengeKind::Script { funbox, .. } | ChallengeKind::Funbox { funbox, .. } => funbox,
+            _ => &[],
+        }
+    }
+
+    /// Whether every funbox of the challenge exists here.
+    pub fn is_supported(&self) -> bool {
+        self.funbox()
+            .iter()
+            .all(|name| funbox::NAMES.contains(&name.as_str()))
+    }
+
+    pub fn difficulty(&self) -> Difficulty {
+        match &self.kind {
+            ChallengeKind::Funbox { difficulty, .. } => *difficulty,
+            _ => Difficulty::Normal,
+        }
+    }
+
+    /// The mode the challenge is typed in, with punctuation and numbers if the requirements ask
+    /// for them. Accuracy challenges are time tests as long as their minimum time.
+    pub fn mode(&self) -> crate::Result<Mode> {
+        let mut mode = match &self.kind {
+            ChallengeKind::CustomTime { seconds } => Mode::time(Seconds::Custom(*seconds)),
+            ChallengeKind::CustomWords { words } => Mode::words(WordCount::Custom(*words)),
+            ChallengeKind::CustomText { text, order, words } => {
+                let words_of_text = text.split_whitespace().map(String::from).collect();
+                Mode::custom(words_of_text, *order, *words)
+            }
+            ChallengeKind::Script { file, .. } => {
+                let script = String::from_utf8_lossy(&crate::dirs::read(Self::path(file))?).into_owned();
+                let words: Vec<String> = script.split_whitespace().map(String::from).collect();
+                let count = words.len();
+                Mode::custom(words, TextOrder::Repeat, count)
+            }
+            ChallengeKind::Funbox { mode, length, .. } => match mode {
+                ModeKind::Time => Mode::time(Seconds::Custom(*length)),
+                _ => Mode::words(WordCount::Custom(*length)),
+            },
+            ChallengeKind::Accuracy => {
+                let seconds = self.requirements.time.as_ref().and_then(|time| time.min);
+                Mode::time(Seconds::Custom(seconds.unwrap_or(60.0) as usize))
+            }
+        };
+
+        let config = &self.requirements.config;
+        if config.get("punctuation") == Some(&Value::Bool(true)) {
+            mode = mode.punctuation();
+        }
+        if config.get("numbers") == Some(&Value::Bool(true)) {
+            mode = mode.numbers();
+        }
+
+        Ok(mode)
+    }
+
+    /// The language the requirements ask for, if any.
+    pub fn language(&self) -> crate::Result<Option<Language>> {
+        match self.requirements.config.get("language") {
+            Some(Value::String(name)) => Ok(Some(name.parse()?)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Describes why `result` doesn't complete the challenge. Empty if it does.
+    pub fn failures(&self, result: &TestResult) -> Vec<String> {
+        let mut failures = Vec::new();
+        if !result.is_completed() {
+            failures.push("the test wasn't completed".to_string());
+        }
+        failures.extend(self.requirements.failures(result));
+
+        if self.funbox().iter().any(|name| name == "layoutfluid") {
+            for layout in &result.layouts {
+                if layout.wpm < LAYOUTFLUID_MIN_WPM {
+                    failures.push(format!(
+                        "{} wpm in {} is not at least {LAYOUTFLUID_MIN_WPM}",
+                        layout.wpm.round(),
+                        layout.layout
+                    ));
+                }
+            }
+        }
+
+        failures
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_all() {
+        let challenges = Challenge::all().unwrap();
+        assert!(challenges.len() > 50);
+
+        let simp = Challenge::load("bigramSalad").unwrap();
+        assert!(matches!(
+            simp.kind,
+            ChallengeKind::CustomText {
+                order: TextOrder::Random,
+                words: 100,
+                ..
+            }
+        ));
+
+        let mnemonist = Challenge::load("mnemonist").unwrap();
+        assert_eq!(mnemonist.difficulty(), Difficulty::Master);
+        assert!(!mnemonist.is_supported());
+
+        for challenge in challenges.iter().filter(|c| c.is_supported()) {
+            assert!(challenge.mode().is_ok(), "{}", challenge.name);
+            assert!(challenge.language().is_ok(), "{}", challenge.name);
+        }
+
+        let english_master = Challenge::load("englishMaster").unwrap();
+        assert_eq!(english_master.language().unwrap(), Some(Language::English10k));
+        assert!(matches!(
+            english_master.mode().unwrap(),
+            Mode::Time {
+                punctuation: true,
+                numbers: true,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn bounds() {
+        let bounds: Bounds = serde_json::from_str(r#"{"min": 60, "max": 70}"#).unwrap();
+        assert!(bounds.contains(60.0));
+        assert!(!bounds.contains(70.5));
+        assert_eq!(bounds.to_string(), "at least 60 and at most 70");
+
+        let exact: Bounds = serde_json::from_str(r#"{"exact": 69}"#).unwrap();
+        assert!(exact.contains(69.4));
+        assert!(!exact.contains(69.5));
+    }
+}
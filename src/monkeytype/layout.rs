@@ -0,0 +1,263 @@
+pub mod analysis;
+
+use serde::{Deserialize, Serialize, de, ser};
+
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+const LAYOUTS_DIR: &str = "layouts";
+const DEFAULT_LAYOUT: &str = "qwerty";
+
+/// The layout physical keys are assumed to have when emulating another layout.
+static QWERTY: LazyLock<KeyboardLayout> = LazyLock::new(KeyboardLayout::default);
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LayoutType {
+    #[default]
+    Ansi,
+    Iso,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LayoutFile {
+    #[serde(default)]
+    keymap_show_top_row: bool,
+    #[serde(rename = "type")]
+    kind: LayoutType,
+    keys: LayoutRows,
+}
+
+#[derive(Deserialize)]
+struct LayoutRows {
+    row1: Vec<Vec<String>>,
+    row2: Vec<Vec<String>>,
+    row3: Vec<Vec<String>>,
+    row4: Vec<Vec<String>>,
+    row5: Vec<Vec<String>>,
+}
+
+/// A key with the chars it types, unshifted first, then shifted and optionally the AltGr
+/// layers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Key {
+    chars: Vec<char>,
+}
+
+impl Key {
+    fn new(chars: Vec<String>) -> Self {
+        Self {
+            chars: chars.iter().filter_map(|c| c.chars().next()).collect(),
+        }
+    }
+
+    pub fn chars(&self) -> &[char] {
+        &self.chars
+    }
+
+    pub fn normal(&self) -> Option<char> {
+        self.chars.first().copied()
+    }
+
+    pub fn shifted(&self) -> Option<char> {
+        self.chars.get(1).copied()
+    }
+}
+
+/// Where a char is on the keyboard.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyPosition {
+    pub row: usize,
+    pub col: usize,
+    /// Whether Shift has to be held to type the char.
+    pub shifted: bool,
+}
+
+/// A keyboard layout from `data/layouts`, with five rows from the number row to the space bar.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyboardLayout {
+    pub name: String,
+    pub kind: LayoutType,
+    /// Whether the number row should be shown in the keymap.
+    pub show_top_row: bool,
+    rows: [Vec<Key>; 5],
+}
+
+impl KeyboardLayout {
+    /// The layout called `name`, relative to the data directories.
+    pub fn path(name: &str) -> PathBuf {
+        Path::new(LAYOUTS_DIR).join(format!("{name}.json"))
+    }
+
+    pub fn load(name: &str) -> crate::Result<Self> {
+        let file = crate::dirs::read_json(Self::path(name))?;
+        Ok(Self::from_file(name, file))
+    }
+
+    fn from_file(name: &str, file: LayoutFile) -> Self {
+        let row = |keys: Vec<Vec<String>>| keys.into_iter().map(Key::new).collect();
+        let LayoutRows {
+            row1,
+            row2,
+            row3,
+            row4,
+            row5,
+        } = file.keys;
+
+        Self {
+            name: name.to_string(),
+            kind: file.kind,
+            show_top_row: file.keymap_show_top_row,
+            rows: [row(row1), row(row2), row(row3), row(row4), row(row5)],
+        }
+    }
+
+    /// Names of all layouts in the `layouts` data directories, sorted case-insensitively.
+    pub fn names() -> crate::Result<Vec<String>> {
+        let mut names = Vec::new();
+        for entry in crate::dirs::read_dir(LAYOUTS_DIR)? {
+            if let Some(name) = entry.name.strip_suffix(".json") {
+                names.push(name.to_string());
+            }
+        }
+        names.sort_by_key(|name| name.to_lowercase());
+
+        Ok(names)
+    }
+
+    pub fn rows(&self) -> &[Vec<Key>; 5] {
+        &self.rows
+    }
+
+    pub fn key(&self, row: usize, col: usize) -> Option<&Key> {
+        self.rows.get(row).and_then(|keys| keys.get(col))
+    }
+
+    /// Finds the key that types `char`, preferring keys that don't need Shift. The space bar is
+    /// the only key in the bottom row, blank keys in the other rows are ignored.
+    pub fn find(&self, char: char) -> Option<KeyPosition> {
+        let position = |shifted: bool| {
+            self.rows.iter().enumerate().find_map(|(row, keys)| {
+                let col = keys.iter().position(|key| {
+                    let c = if shifted { key.shifted() } else { key.normal() };
+                    c == Some(char) && (char != ' ' || row == 4)
+                })?;
+                Some(KeyPosition { row, col, shifted })
+            })
+        };
+
+        position(false).or_else(|| position(true))
+    }
+
+    /// Translates a char typed on a QWERTY keyboard into the char the same physical key types
+    /// in this layout. Shifted chars map through the shifted column. Chars that aren't on a
+    /// QWERTY keyboard and the space bar are left as they are.
+    pub fn emulate(&self, char: char) -> char {
+        let Some(position) = QWERTY.find(char).filter(|p| p.row != 4) else {
+            return char;
+        };
+        // ISO keyboards have an extra key left of Z.
+        let col = match (self.kind, position.row) {
+            (LayoutType::Iso, 3) => position.col + 1,
+            _ => position.col,
+        };
+
+        self.key(position.row, col)
+            .and_then(|key| {
+                if position.shifted {
+                    key.shifted()
+                } else {
+                    key.normal()
+                }
+            })
+            // Unused positions are marked with a space.
+            .filter(|c| *c != ' ')
+            .unwrap_or(char)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let name: String = de::Deserialize::deserialize(deserializer)?;
+        Self::load(&name).map_err(de::Error::custom)
+    }
+
+    pub fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        serializer.serialize_str(&self.name)
+    }
+}
+
+impl Default for KeyboardLayout {
+    fn default() -> Self {
+        let content = include_bytes!("../../data/layouts/qwerty.json");
+        let file = serde_json::from_slice(content).expect("the qwerty layout is valid");
+        Self::from_file(DEFAULT_LAYOUT, file)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// A layout with a key for every char of `rows`, which only types that char.
+    pub(crate) fn layout(kind: LayoutType, rows: [&str; 5]) -> KeyboardLayout {
+        KeyboardLayout {
+            name: "test".to_string(),
+            kind,
+            show_top_row: true,
+            rows: rows.map(|row| row.chars().map(|c| Key { chars: vec![c] }).collect()),
+        }
+    }
+
+    #[test]
+    fn load_all() {
+        let names = KeyboardLayout::names().unwrap();
+        assert!(names.len() > 200);
+        for name in names {
+            let layout = KeyboardLayout::load(&name).unwrap();
+            assert!(layout.find(' ').is_some(), "{name} has no space bar");
+        }
+    }
+
+    #[test]
+    fn find() {
+        let qwerty = KeyboardLayout::default();
+        assert_eq!(
+            qwerty.find('a'),
+            Some(KeyPosition {
+                row: 2,
+                col: 0,
+                shifted: false
+            })
+        );
+        assert_eq!(
+            qwerty.find('!'),
+            Some(KeyPosition {
+                row: 0,
+                col: 1,
+                shifted: true
+            })
+        );
+        assert_eq!(qwerty.find(' ').map(|p| p.row), Some(4));
+        assert_eq!(qwerty.find('ä'), None);
+    }
+
+    #[test]
+    fn emulate() {
+        let colemak = KeyboardLayout::load("colemak").unwrap();
+        assert_eq!(colemak.emulate('e'), 'f');
+        assert_eq!(colemak.emulate('E'), 'F');
+        assert_eq!(colemak.emulate('k'), 'e');
+        assert_eq!(colemak.emulate(' '), ' ');
+        assert_eq!(colemak.emulate('ä'), 'ä');
+
+        let azerty = KeyboardLayout::load("azerty").unwrap();
+        assert_eq!(azerty.emulate('q'), 'a');
+        assert_eq!(azerty.emulate('z'), 'w');
+    }
+}
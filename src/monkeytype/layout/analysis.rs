@@ -0,0 +1,407 @@
+//! Measures how comfortable a [`KeyboardLayout`] is to type a corpus on, assuming standard touch
+//! typing: every column of the letter block belongs to one finger and the space bar to the
+//! thumbs. Shifted chars count as the key they are on, the Shift key itself is ignored.
+
+use super::{KeyboardLayout, LayoutType};
+use crate::monkeytype::{Language, Quotes, Words};
+use crate::typing::replay::{KeystrokeLog, Outcome};
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Write;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Hand {
+    Left,
+    Right,
+}
+
+impl fmt::Display for Hand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let string = match self {
+            Self::Left => "left hand",
+            Self::Right => "right hand",
+        };
+
+        write!(f, "{string}")
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Finger {
+    LeftPinky,
+    LeftRing,
+    LeftMiddle,
+    LeftIndex,
+    Thumb,
+    RightIndex,
+    RightMiddle,
+    RightRing,
+    RightPinky,
+}
+
+impl Finger {
+    pub const ALL: [Self; 9] = [
+        Self::LeftPinky,
+        Self::LeftRing,
+        Self::LeftMiddle,
+        Self::LeftIndex,
+        Self::Thumb,
+        Self::RightIndex,
+        Self::RightMiddle,
+        Self::RightRing,
+        Self::RightPinky,
+    ];
+
+    /// The finger for a column of the letter block, where column 0 is the one of `Q` and `A`.
+    fn from_column(column: i32) -> Self {
+        match column {
+            ..=0 => Self::LeftPinky,
+            1 => Self::LeftRing,
+            2 => Self::LeftMiddle,
+            3 | 4 => Self::LeftIndex,
+            5 | 6 => Self::RightIndex,
+            7 => Self::RightMiddle,
+            8 => Self::RightRing,
+            _ => Self::RightPinky,
+        }
+    }
+
+    /// The hand the finger belongs to. The thumbs are shared by both.
+    pub fn hand(&self) -> Option<Hand> {
+        match *self as usize {
+            0..4 => Some(Hand::Left),
+            4 => None,
+            _ => Some(Hand::Right),
+        }
+    }
+
+    /// Whether `other` is the next finger on the same hand.
+    fn is_adjacent(&self, other: &Self) -> bool {
+        self.hand().is_some()
+            && self.hand() == other.hand()
+            && (*self as usize).abs_diff(*other as usize) == 1
+    }
+}
+
+impl fmt::Display for Finger {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let string = match self {
+            Self::LeftPinky => "left pinky",
+            Self::LeftRing => "left ring",
+            Self::LeftMiddle => "left middle",
+            Self::LeftIndex => "left index",
+            Self::Thumb => "thumbs",
+            Self::RightIndex => "right index",
+            Self::RightMiddle => "right middle",
+            Self::RightRing => "right ring",
+            Self::RightPinky => "right pinky",
+        };
+
+        write!(f, "{string}")
+    }
+}
+
+/// How often every char and every pair of consecutive chars occur in a text.
+#[derive(Debug, Default)]
+pub struct Corpus {
+    chars: HashMap<char, u64>,
+    bigrams: HashMap<(char, char), u64>,
+}
+
+impl Corpus {
+    /// Every word of the language's word list once, each followed by a space.
+    pub fn from_language(language: &Language) -> crate::Result<Self> {
+        let words = Words::from_language(language)?;
+        let mut corpus = Self::default();
+        for word in words.words() {
+            corpus.add(&format!("{word} "));
+        }
+
+        Ok(corpus)
+    }
+
+    /// The text of every quote of the language.
+    pub fn from_quotes(language: &Language) -> crate::Result<Self> {
+        let quotes =
+            Quotes::from_language(language)?.ok_or(crate::Error::NoQuotesForLanguage(*language))?;
+        let mut corpus = Self::default();
+        for quote in quotes.quotes() {
+            corpus.add(&quote.text);
+        }
+
+        Ok(corpus)
+    }
+
+    /// Everything that was typed in the saved replays, including the mistakes. A backspace ends
+    /// a run of chars, so no bigram spans it. Replays that can't be loaded are added to `errors`.
+    pub fn from_history(errors: &mut Vec<crate::Error>) -> crate::Result<Self> {
+        let mut corpus = Self::default();
+        for log in KeystrokeLog::load_all(errors)? {
+            let mut text = String::new();
+            for keystroke in log.keystrokes() {
+                match (keystroke.outcome, keystroke.key) {
+                    (Outcome::Backspace, _) => {
+                        corpus.add(&text);
+                        text.clear();
+                    }
+                    (_, Some(key)) => text.push(key),
+                    _ => (),
+                }
+            }
+            corpus.add(&text);
+        }
+
+        Ok(corpus)
+    }
+
+    pub fn add(&mut self, text: &str) {
+        let mut previous = None;
+        for char in text.chars() {
+            *self.chars.entry(char).or_default() += 1;
+            if let Some(previous) = previous {
+                *self.bigrams.entry((previous, char)).or_default() += 1;
+            }
+            previous = Some(char);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chars.is_empty()
+    }
+}
+
+/// Where and with which finger a char is typed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Press {
+    finger: Finger,
+    row: usize,
+    /// The column in the letter block, so that keys above each other share a column.
+    column: i32,
+}
+
+impl Press {
+    fn find(layout: &KeyboardLayout, char: char) -> Option<Self> {
+        let position = layout.find(char)?;
+        let column = match (position.row, layout.kind) {
+            (4, _) => {
+                return Some(Self {
+                    finger: Finger::Thumb,
+                    row: 4,
+                    column: 0,
+                });
+            }
+            // The number row starts half a key further left and ISO keyboards have an extra key
+            // left of Z.
+            (0, _) | (3, LayoutType::Iso) => position.col as i32 - 1,
+            _ => position.col as i32,
+        };
+
+        Some(Self {
+            finger: Finger::from_column(column),
+            row: position.row,
+            column,
+        })
+    }
+}
+
+/// The metrics of one layout for one corpus. Percentages of chars are relative to the chars the
+/// layout has a key for, percentages of bigrams to the bigrams of those chars.
+#[derive(Clone, Debug, Default)]
+pub struct Analysis {
+    pub layout: String,
+    chars: u64,
+    missing: u64,
+    fingers: [u64; 9],
+    bigrams: u64,
+    same_finger: u64,
+    lateral_stretches: u64,
+    scissors: u64,
+    row_jumps: u64,
+}
+
+impl Analysis {
+    pub fn new(layout: &KeyboardLayout, corpus: &Corpus) -> Self {
+        let mut analysis = Self {
+            layout: layout.name.clone(),
+            ..Default::default()
+        };
+        let mut presses = HashMap::new();
+        let mut press = |char: char| {
+            *presses
+                .entry(char)
+                .or_insert_with(|| Press::find(layout, char))
+        };
+
+        for (char, count) in &corpus.chars {
+            match press(*char) {
+                Some(press) => {
+                    analysis.chars += count;
+                    analysis.fingers[press.finger as usize] += count;
+                }
+                None => analysis.missing += count,
+            }
+        }
+
+        for ((first, second), count) in &corpus.bigrams {
+            let (Some(first), Some(second)) = (press(*first), press(*second)) else {
+                continue;
+            };
+            analysis.bigrams += count;
+
+            // Bigrams that use both hands or the thumbs are easy to type.
+            if first.finger.hand().is_none() || first.finger.hand() != second.finger.hand() {
+                continue;
+            }
+            let rows = first.row.abs_diff(second.row);
+            let columns = first.column.abs_diff(second.column);
+
+            if first.finger == second.finger && (rows, columns) != (0, 0) {
+                analysis.same_finger += count;
+            }
+            if first.finger.is_adjacent(&second.finger) {
+                if columns >= 2 {
+                    analysis.lateral_stretches += count;
+                }
+                if rows >= 2 {
+                    analysis.scissors += count;
+                }
+            }
+            if rows >= 2 {
+                analysis.row_jumps += count;
+            }
+        }
+
+        analysis
+    }
+
+    /// Percentage of the corpus' chars the layout has a key for.
+    pub fn coverage(&self) -> f32 {
+        percent(self.chars, self.chars + self.missing)
+    }
+
+    pub fn finger_usage(&self, finger: Finger) -> f32 {
+        percent(self.fingers[finger as usize], self.chars)
+    }
+
+    /// Share of the hand in the keys typed without the thumbs.
+    pub fn hand_usage(&self, hand: Hand) -> f32 {
+        let count = |hand: Hand| {
+            Finger::ALL
+                .iter()
+                .filter(|finger| finger.hand() == Some(hand))
+                .map(|finger| self.fingers[*finger as usize])
+                .sum::<u64>()
+        };
+
+        percent(count(hand), count(Hand::Left) + count(Hand::Right))
+    }
+
+    /// Bigrams typed with the same finger on two different keys.
+    pub fn same_finger_bigrams(&self) -> f32 {
+        percent(self.same_finger, self.bigrams)
+    }
+
+    /// Bigrams on adjacent fingers of one hand whose keys are at least two columns apart, like
+    /// `ge` on QWERTY.
+    pub fn lateral_stretches(&self) -> f32 {
+        percent(self.lateral_stretches, self.bigrams)
+    }
+
+    /// Bigrams on adjacent fingers of one hand whose keys are at least two rows apart, like
+    /// `cr` on QWERTY.
+    pub fn scissors(&self) -> f32 {
+        percent(self.scissors, self.bigrams)
+    }
+
+    /// Bigrams on one hand whose keys are at least two rows apart, with any fingers.
+    pub fn row_jumps(&self) -> f32 {
+        percent(self.row_jumps, self.bigrams)
+    }
+}
+
+fn percent(count: u64, total: u64) -> f32 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f32 / total as f32 * 100.0
+    }
+}
+
+/// Formats the analyses as a table with one column per layout.
+pub fn table(analyses: &[Analysis]) -> String {
+    const LABEL_WIDTH: usize = 20;
+
+    let values = |value: &dyn Fn(&Analysis) -> f32| analyses.iter().map(value).collect();
+    let mut rows: Vec<(String, Vec<f32>)> =
+        vec![("coverage".to_string(), values(&Analysis::coverage))];
+    for finger in Finger::ALL {
+        rows.push((finger.to_string(), values(&|a| a.finger_usage(finger))));
+    }
+    for hand in [Hand::Left, Hand::Right] {
+        rows.push((hand.to_string(), values(&|a| a.hand_usage(hand))));
+    }
+    rows.push((
+        "same finger bigrams".to_string(),
+        values(&Analysis::same_finger_bigrams),
+    ));
+    rows.push((
+        "lateral stretches".to_string(),
+        values(&Analysis::lateral_stretches),
+    ));
+    rows.push(("scissors".to_string(), values(&Analysis::scissors)));
+    rows.push(("row jumps".to_string(), values(&Analysis::row_jumps)));
+
+    let widths: Vec<usize> = analyses.iter().map(|a| a.layout.len().max(7) + 2).collect();
+
+    let mut table = format!("{:LABEL_WIDTH$}", "");
+    for (analysis, width) in analyses.iter().zip(&widths) {
+        let _ = write!(table, "{:>width$}", analysis.layout);
+    }
+    for (label, values) in rows {
+        let _ = write!(table, "\n{label:LABEL_WIDTH$}");
+        for (value, width) in values.iter().zip(&widths) {
+            let _ = write!(table, "{:>width$}", format!("{value:.2}%"));
+        }
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analyze(text: &str) -> Analysis {
+        let mut corpus = Corpus::default();
+        corpus.add(text);
+        Analysis::new(&KeyboardLayout::default(), &corpus)
+    }
+
+    #[test]
+    fn bigrams() {
+        // Same finger on different keys, but not the same key twice.
+        assert_eq!(analyze("ed").same_finger_bigrams(), 100.0);
+        assert_eq!(analyze("ee").same_finger_bigrams(), 0.0);
+
+        let lateral = analyze("ge");
+        assert_eq!(lateral.lateral_stretches(), 100.0);
+        assert_eq!(lateral.scissors(), 0.0);
+
+        let scissor = analyze("cr");
+        assert_eq!(scissor.scissors(), 100.0);
+        assert_eq!(scissor.row_jumps(), 100.0);
+
+        // Alternating hands.
+        let alternating = analyze("ej");
+        assert_eq!(alternating.same_finger_bigrams(), 0.0);
+        assert_eq!(alternating.hand_usage(Hand::Left), 50.0);
+    }
+
+    #[test]
+    fn coverage() {
+        let analysis = analyze("aä");
+        assert_eq!(analysis.coverage(), 50.0);
+        assert_eq!(analysis.finger_usage(Finger::LeftPinky), 100.0);
+    }
+}
@@ -1,12 +1,10 @@
 use super::Language;
-use crate::DATA_DIR;
 use crate::typing::QuoteLength;
 
 use serde::{Deserialize, Serialize};
 
 use std::fmt;
-use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 const QUOTES_DIR: &str = "quotes";
 
@@ -35,17 +33,15 @@ impl Quotes {
     }
 
     pub fn from_quote_language(quote_language: QuoteLanguage) -> crate::Result<Option<Self>> {
-        Self::from_file(quote_language.file())
+        match crate::dirs::read_json(quote_language.file()) {
+            Ok(quotes) => Ok(Some(quotes)),
+            Err(crate::Error::NotFound { .. }) => Ok(None),
+            Err(err) => Err(err),
+        }
     }
 
-    fn from_file(file_path: PathBuf) -> crate::Result<Option<Self>> {
-        if let Ok(contents) = fs::read(file_path) {
-            let quotes: Quotes = serde_json::from_slice(&contents)?;
-
-            Ok(Some(quotes))
-        } else {
-            Ok(None)
-        }
+    pub fn quotes(&self) -> &[Quote] {
+        &self.quotes
     }
 
     pub fn random(&self, quote_lengths: &Vec<QuoteLength>) -> Option<&Quote> {
@@ -145,12 +141,9 @@ pub enum QuoteLanguage {
 }
 
 impl QuoteLanguage {
-    pub fn dir() -> PathBuf {
-        PathBuf::from(DATA_DIR).join(QUOTES_DIR)
-    }
-
+    /// The quotes of the language, relative to the data directories.
     pub fn file(&self) -> PathBuf {
-        QuoteLanguage::dir().join(format!("{self}.json"))
+        Path::new(QUOTES_DIR).join(format!("{self}.json"))
     }
 
     pub fn from_language(language: &Language) -> crate::Result<QuoteLanguage> {
@@ -1,12 +1,11 @@
-use crate::DATA_DIR;
 use crate::typing::WordCount;
 
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 
 use std::fmt;
-use std::fs;
-use std::path::PathBuf;
+use std::str::FromStr;
+use std::path::{Path, PathBuf};
 
 const LANGUAGES_DIR: &str = "languages";
 
@@ -32,13 +31,11 @@ fn false_bool() -> bool {
 
 impl Words {
     pub fn from_language(language: &Language) -> crate::Result<Self> {
-        Self::from_file(language.file())
+        crate::dirs::read_json(language.file())
     }
 
-    fn from_file(file: PathBuf) -> crate::Result<Self> {
-        let contents = fs::read(file)?;
-        let json: Self = serde_json::from_slice(&contents)?;
-        Ok(json)
+    pub fn words(&self) -> &[String] {
+        &self.words
     }
 
     pub fn random(&self, count: &WordCount, punctuation: bool, numbers: bool) -> Option<Vec<&String>> {
@@ -57,7 +54,7 @@ impl Words {
     }
 }
 
-#[derive(Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
 pub enum Language {
     Afrikaans10k,
     Afrikaans1k,
@@ -483,12 +480,433 @@ pub enum Language {
 }
 
 impl Language {
-    pub fn dir() -> PathBuf {
-        PathBuf::from(DATA_DIR).join(LANGUAGES_DIR)
-    }
+    pub const ALL: &[Language] = &[
+        Language::Afrikaans10k,
+        Language::Afrikaans1k,
+        Language::Afrikaans,
+        Language::Albanian1k,
+        Language::Albanian,
+        Language::Amharic1k,
+        Language::Amharic5k,
+        Language::Amharic,
+        Language::Arabic10k,
+        Language::ArabicEgypt1k,
+        Language::ArabicEgypt,
+        Language::ArabicMorocco,
+        Language::Arabic,
+        Language::Armenian1k,
+        Language::ArmenianWestern1k,
+        Language::ArmenianWestern,
+        Language::Armenian,
+        Language::Azerbaijani1k,
+        Language::Azerbaijani,
+        Language::Bangla10k,
+        Language::BanglaLetters,
+        Language::Bangla,
+        Language::Bashkir,
+        Language::Belarusian100k,
+        Language::Belarusian10k,
+        Language::Belarusian1k,
+        Language::Belarusian25k,
+        Language::Belarusian50k,
+        Language::Belarusian5k,
+        Language::BelarusianLacinka1k,
+        Language::BelarusianLacinka,
+        Language::Belarusian,
+        Language::Bosnian4k,
+        Language::Bosnian,
+        Language::BulgarianLatin,
+        Language::Bulgarian,
+        Language::Catalan1k,
+        Language::Catalan,
+        Language::ChineseSimplified10k,
+        Language::ChineseSimplified1k,
+        Language::ChineseSimplified50k,
+        Language::ChineseSimplified5k,
+        Language::ChineseSimplified,
+        Language::ChineseTraditional,
+        Language::CodeAbap1k,
+        Language::CodeAbap,
+        Language::CodeArduino,
+        Language::CodeAssembly,
+        Language::CodeBash,
+        Language::CodeBrainfck,
+        Language::CodeC,
+        Language::CodeCpp,
+        Language::CodeCobol,
+        Language::CodeCommonLisp,
+        Language::CodeCSharp,
+        Language::CodeCSS,
+        Language::CodeCuda,
+        Language::CodeDart,
+        Language::CodeElixir,
+        Language::CodeFortran,
+        Language::CodeFSharp,
+        Language::CodeGdscript2,
+        Language::CodeGdscript,
+        Language::CodeGleam,
+        Language::CodeGo,
+        Language::CodeHaskell,
+        Language::CodeHtml,
+        Language::CodeJava,
+        Language::CodeJavascript1k,
+        Language::CodeJavascriptReact,
+        Language::CodeJavascript,
+        Language::CodeJule,
+        Language::CodeJulia,
+        Language::CodeKotlin,
+        Language::CodeLatex,
+        Language::CodeLua,
+        Language::CodeLuau,
+        Language::CodeMatlab,
+        Language::CodeNim,
+        Language::CodeNix,
+        Language::CodeOdin,
+        Language::CodeOok,
+        Language::CodeOpenCL,
+        Language::CodePascal,
+        Language::CodePerl,
+        Language::CodePhp,
+        Language::CodePowershell,
+        Language::CodePython1k,
+        Language::CodePython2k,
+        Language::CodePython5k,
+        Language::CodePython,
+        Language::CodeR2k,
+        Language::CodeR,
+        Language::CodeRockstar,
+        Language::CodeRuby,
+        Language::CodeRust,
+        Language::CodeScala,
+        Language::CodeSQL,
+        Language::CodeSwift,
+        Language::CodeSystemVerilog,
+        Language::CodeTypescript,
+        Language::CodeTypst,
+        Language::CodeV,
+        Language::CodeVim,
+        Language::CodeVimscript,
+        Language::CodeVisualBasic,
+        Language::CodeYoptascript,
+        Language::CodeZig,
+        Language::Croatian1k,
+        Language::Croatian,
+        Language::Czech10k,
+        Language::Czech1k,
+        Language::Czech,
+        Language::Danish10k,
+        Language::Danish1k,
+        Language::Danish,
+        Language::DockerFile,
+        Language::Dutch10k,
+        Language::Dutch1k,
+        Language::Dutch,
+        Language::English10k,
+        Language::English1k,
+        Language::English25k,
+        Language::English450k,
+        Language::English5k,
+        Language::EnglishCommonlyMisspelled,
+        Language::EnglishContractions,
+        Language::EnglishDoubleletter,
+        Language::EnglishMedical,
+        Language::EnglishOld,
+        Language::EnglishShakespearean,
+        Language::English,
+        Language::Esperanto10k,
+        Language::Esperanto1k,
+        Language::Esperanto25k,
+        Language::Esperanto36k,
+        Language::EsperantoHSistemo10k,
+        Language::EsperantoHSistemo1k,
+        Language::EsperantoHSistemo25k,
+        Language::EsperantoHSistemo36k,
+        Language::EsperantoHSistemo,
+        Language::EsperantoXSistemo10k,
+        Language::EsperantoXSistemo1k,
+        Language::EsperantoXSistemo25k,
+        Language::EsperantoXSistemo36k,
+        Language::EsperantoXSistemo,
+        Language::Esperanto,
+        Language::Estonian10k,
+        Language::Estonian1k,
+        Language::Estonian5k,
+        Language::Estonian,
+        Language::Euskera,
+        Language::Filipino1k,
+        Language::Filipino,
+        Language::Finnish10k,
+        Language::Finnish1k,
+        Language::Finnish,
+        Language::French10k,
+        Language::French1k,
+        Language::French2k,
+        Language::French600k,
+        Language::FrenchBitoduc,
+        Language::French,
+        Language::Frisian1k,
+        Language::Frisian,
+        Language::Friulian,
+        Language::Galician,
+        Language::Georgian,
+        Language::German10k,
+        Language::German1k,
+        Language::German250k,
+        Language::German,
+        Language::Git,
+        Language::Greek10k,
+        Language::Greek1k,
+        Language::Greek25k,
+        Language::Greek5k,
+        Language::Greek,
+        Language::Greeklish10k,
+        Language::Greeklish1k,
+        Language::Greeklish25k,
+        Language::Greeklish5k,
+        Language::Greeklish,
+        Language::Gujarati1k,
+        Language::Gujarati,
+        Language::Hausa1k,
+        Language::Hausa,
+        Language::Hawaiian1k,
+        Language::Hawaiian,
+        Language::Hebrew10k,
+        Language::Hebrew1k,
+        Language::Hebrew5k,
+        Language::Hebrew,
+        Language::Hindi1k,
+        Language::Hindi,
+        Language::Hinglish,
+        Language::Hungarian2k,
+        Language::Hungarian,
+        Language::Icelandic1k,
+        Language::Icelandic,
+        Language::Indonesian10k,
+        Language::Indonesian1k,
+        Language::Indonesian,
+        Language::Irish,
+        Language::Italian1k,
+        Language::Italian280k,
+        Language::Italian60k,
+        Language::Italian7k,
+        Language::Italian,
+        Language::JapaneseHiragana,
+        Language::JapaneseKatakana,
+        Language::JapaneseRomaji1k,
+        Language::JapaneseRomaji,
+        Language::Jyutping,
+        Language::Kabyle10k,
+        Language::Kabyle1k,
+        Language::Kabyle2k,
+        Language::Kabyle5k,
+        Language::Kabyle,
+        Language::Kannada,
+        Language::Kazakh1k,
+        Language::Kazakh,
+        Language::Khmer,
+        Language::Klingon1k,
+        Language::Klingon,
+        Language::Korean1k,
+        Language::Korean5k,
+        Language::Korean,
+        Language::KurdishCentral2k,
+        Language::KurdishCentral4k,
+        Language::KurdishCentral,
+        Language::Kyrgyz1k,
+        Language::Kyrgyz,
+        Language::Latin,
+        Language::Latvian1k,
+        Language::Latvian,
+        Language::LeagueOfLegends,
+        Language::Lithuanian1k,
+        Language::Lithuanian3k,
+        Language::Lithuanian,
+        Language::LojbanCmavo,
+        Language::LojbanGismu,
+        Language::LoremIpsum,
+        Language::Macedonian10k,
+        Language::Macedonian1k,
+        Language::Macedonian75k,
+        Language::Macedonian,
+        Language::Malagasy1k,
+        Language::Malagasy,
+        Language::Malay1k,
+        Language::Malay,
+        Language::Malayalam,
+        Language::Maltese1k,
+        Language::Maltese,
+        Language::Maori1k,
+        Language::Marathi,
+        Language::Mongolian10k,
+        Language::Mongolian,
+        Language::MyanmarBurmese,
+        Language::Nepali1k,
+        Language::NepaliRomanized,
+        Language::Nepali,
+        Language::NorwegianBokmal10k,
+        Language::NorwegianBokmal150k,
+        Language::NorwegianBokmal1k,
+        Language::NorwegianBokmal5k,
+        Language::NorwegianBokmal600k,
+        Language::NorwegianBokmal,
+        Language::NorwegianNynorsk100k,
+        Language::NorwegianNynorsk10k,
+        Language::NorwegianNynorsk1k,
+        Language::NorwegianNynorsk400k,
+        Language::NorwegianNynorsk5k,
+        Language::NorwegianNynorsk,
+        Language::Occitan10k,
+        Language::Occitan1k,
+        Language::Occitan2k,
+        Language::Occitan5k,
+        Language::Occitan,
+        Language::Oromo1k,
+        Language::Oromo5k,
+        Language::Oromo,
+        Language::Pashto,
+        Language::Persian1k,
+        Language::Persian20k,
+        Language::Persian5k,
+        Language::PersianRomanized,
+        Language::Persian,
+        Language::PigLatin,
+        Language::Pinyin10k,
+        Language::Pinyin1k,
+        Language::Pinyin,
+        Language::Polish10k,
+        Language::Polish200k,
+        Language::Polish20k,
+        Language::Polish2k,
+        Language::Polish40k,
+        Language::Polish5k,
+        Language::Polish,
+        Language::Portuguese1k,
+        Language::Portuguese320k,
+        Language::Portuguese3k,
+        Language::Portuguese550k,
+        Language::Portuguese5k,
+        Language::PortugueseAcentosECedilha,
+        Language::Portuguese,
+        Language::Quenya,
+        Language::Romanian100k,
+        Language::Romanian10k,
+        Language::Romanian1k,
+        Language::Romanian200k,
+        Language::Romanian25k,
+        Language::Romanian50k,
+        Language::Romanian5k,
+        Language::Romanian,
+        Language::Russian10k,
+        Language::Russian1k,
+        Language::Russian25k,
+        Language::Russian375k,
+        Language::Russian50k,
+        Language::Russian5k,
+        Language::RussianAbbreviations,
+        Language::RussianContractions1k,
+        Language::RussianContractions,
+        Language::Russian,
+        Language::SanskritRoman,
+        Language::Sanskrit,
+        Language::Santali,
+        Language::Serbian10k,
+        Language::SerbianLatin10k,
+        Language::SerbianLatin,
+        Language::Serbian,
+        Language::Shona1k,
+        Language::Shona,
+        Language::Sinhala,
+        Language::Slovak10k,
+        Language::Slovak1k,
+        Language::Slovak,
+        Language::Slovenian1k,
+        Language::Slovenian5k,
+        Language::Slovenian,
+        Language::Spanish10k,
+        Language::Spanish1k,
+        Language::Spanish650k,
+        Language::Spanish,
+        Language::Swahili1k,
+        Language::Swedish1k,
+        Language::SwedishDiacritics,
+        Language::Swedish,
+        Language::SwissGerman1k,
+        Language::SwissGerman2k,
+        Language::SwissGerman,
+        Language::Tamil1k,
+        Language::TamilOld,
+        Language::Tamil,
+        Language::Tanglish,
+        Language::Tatar1k,
+        Language::Tatar5k,
+        Language::Tatar9k,
+        Language::TatarCrimean10k,
+        Language::TatarCrimean15k,
+        Language::TatarCrimean1k,
+        Language::TatarCrimean5k,
+        Language::TatarCrimeanCyrillic10k,
+        Language::TatarCrimeanCyrillic15k,
+        Language::TatarCrimeanCyrillic1k,
+        Language::TatarCrimeanCyrillic5k,
+        Language::TatarCrimeanCyrillic,
+        Language::TatarCrimean,
+        Language::Tatar,
+        Language::Telugu1k,
+        Language::Telugu,
+        Language::Thai10k,
+        Language::Thai1k,
+        Language::Thai20k,
+        Language::Thai50k,
+        Language::Thai5k,
+        Language::Thai60k,
+        Language::Thai,
+        Language::Tibetan1k,
+        Language::Tibetan,
+        Language::TokiPonaKuLili,
+        Language::TokiPonaKuSuli,
+        Language::TokiPona,
+        Language::Turkish1k,
+        Language::Turkish5k,
+        Language::Turkish,
+        Language::TwitchEmotes,
+        Language::TypingOfTheDead,
+        Language::Udmurt,
+        Language::Ukrainian10k,
+        Language::Ukrainian1k,
+        Language::Ukrainian50k,
+        Language::UkrainianEndings,
+        Language::UkrainianLatynka10k,
+        Language::UkrainianLatynka1k,
+        Language::UkrainianLatynka50k,
+        Language::UkrainianLatynkaEndings,
+        Language::UkrainianLatynka,
+        Language::Ukrainian,
+        Language::Urdish,
+        Language::Urdu1k,
+        Language::Urdu5k,
+        Language::Urdu,
+        Language::Uzbek1k,
+        Language::Uzbek70k,
+        Language::Uzbek,
+        Language::Vietnamese1k,
+        Language::Vietnamese5k,
+        Language::Vietnamese,
+        Language::ViossaNjutro,
+        Language::Viossa,
+        Language::Welsh1k,
+        Language::Welsh,
+        Language::Wordle1k,
+        Language::Wordle,
+        Language::Xhosa3k,
+        Language::Xhosa,
+        Language::Yiddish,
+        Language::Yoruba1k,
+        Language::Zulu,
+    ];
 
+    /// The word list of the language, relative to the data directories.
     pub fn file(&self) -> PathBuf {
-        Self::dir().join(format!("{self}.json"))
+        Path::new(LANGUAGES_DIR).join(format!("{self}.json"))
     }
 }
 
@@ -922,6 +1340,18 @@ impl fmt::Display for Language {
     }
 }
 
+impl FromStr for Language {
+    type Err = crate::Error;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .find(|language| language.to_string() == name)
+            .copied()
+            .ok_or_else(|| crate::Error::UnknownLanguage(name.to_string()))
+    }
+}
+
 impl Default for Language {
     fn default() -> Self {
         Self::English
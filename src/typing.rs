@@ -1,74 +1,256 @@
+pub mod feedback;
+pub mod funbox;
+pub mod keyboard;
 pub mod mode;
+pub mod replay;
 pub mod statistics;
 
-pub use mode::{Mode, QuoteLength, Seconds, WordCount};
+pub use feedback::{ErrorFeedback, Feedback};
+pub use funbox::{Funbox, Funboxes};
+pub use keyboard::{Heatmap, KEYBOARD_WIDTH, Keyboard};
+pub use mode::{Difficulty, Mode, QuoteLength, Seconds, TextOrder, WordCount};
+pub use replay::{KeystrokeLog, Replay, ReplaySpeed};
 pub use statistics::TestStatistics;
 
-use crate::monkeytype::{Language, MonkeyType};
+use crate::audio::{Audio, NullSink};
+use crate::monkeytype::{Challenge, KeyboardLayout, Language, MonkeyType};
+use crate::user::stats::{
+    CharCounts, KeyStats, ModeKind, PbComparison, ResultStatus, TestResult,
+};
+use feedback::{Effects, Mistake};
+use funbox::TextChar;
+use replay::Outcome;
 
 use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
-use ratatui::{
-    prelude::*,
-    widgets::{Paragraph, Wrap},
-};
+use ratatui::{prelude::*, widgets::Paragraph};
 
 use std::collections::HashSet;
+use std::ops::Range;
+use std::time::{Duration, Instant};
+
+/// Keys and bigrams typed less often in a test are left out of the results screen.
+const MIN_KEY_SAMPLES: u32 = 2;
+/// How long the key that was pressed stays highlighted in the keymap.
+const KEY_FLASH: Duration = Duration::from_millis(200);
+/// Words generated at once for time mode. More are added while the test runs.
+const TIME_MODE_WORDS: usize = 50;
+
+/// The settings a test is started with, restored when a test can't be started with others.
+type Settings = (Mode, Language, Difficulty, Option<Challenge>);
 
 pub struct TestState {
     language: Language,
     mode: Mode,
+    difficulty: Difficulty,
+    quote: Option<(usize, QuoteLength)>,
     test_text: String,
     typed_text: Vec<char>,
     was_typed_wrong: HashSet<usize>,
-    monkey: MonkeyType,
+    /// The words and quotes of the language, loaded by the first test.
+    monkey: Option<MonkeyType>,
     statistics: TestStatistics,
+    keystrokes: KeystrokeLog,
+    replay: Option<Replay>,
+    finished: bool,
+    results: Vec<TestResult>,
+    /// Key statistics of the tests in `results`.
+    key_stats: KeyStats,
+    last_result: Option<TestResult>,
+    last_key_stats: KeyStats,
+    personal_best: Option<PbComparison>,
+    heatmap: Heatmap,
+    /// The last typed char, whether it was correct and when it was typed.
+    last_key: Option<(char, bool, Instant)>,
+    funboxes: Funboxes,
+    challenge: Option<Challenge>,
+    /// Why the last test didn't complete the challenge, empty if it did.
+    challenge_failures: Option<Vec<String>>,
+    /// Names of the challenges completed by tests in `results`, with the timestamp of the
+    /// result.
+    completed_challenges: Vec<(String, u64)>,
+    audio: Audio,
+    effects: Effects,
+}
+
+impl Default for TestState {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl TestState {
-    pub fn new() -> crate::Result<Self> {
-        let language = Language::default();
-        Ok(Self {
-            monkey: MonkeyType::new(language)?,
+    pub fn new() -> Self {
+        Self {
+            monkey: None,
             was_typed_wrong: HashSet::new(),
-            language,
+            language: Language::default(),
             mode: Mode::default(),
+            difficulty: Difficulty::default(),
+            quote: None,
             test_text: String::new(),
             typed_text: Vec::new(),
             statistics: TestStatistics::new(),
-        })
+            keystrokes: KeystrokeLog::default(),
+            replay: None,
+            finished: false,
+            results: Vec::new(),
+            key_stats: KeyStats::default(),
+            last_result: None,
+            last_key_stats: KeyStats::default(),
+            personal_best: None,
+            heatmap: Heatmap::default(),
+            last_key: None,
+            funboxes: Funboxes::default(),
+            challenge: None,
+            challenge_failures: None,
+            completed_challenges: Vec::new(),
+            audio: Audio::system(),
+            effects: Effects::default(),
+        }
     }
 
-    pub fn new_test(&mut self) -> crate::Result<()> {
-        if self.monkey.language != self.language {
-            self.monkey.set_language(self.language)?;
+    /// Starts a new test. The current test is kept if the words, quotes, sounds or funboxes
+    /// can't be loaded.
+    pub fn new_test(&mut self, config: &crate::Config) -> crate::Result<()> {
+        match &mut self.monkey {
+            Some(monkey) if monkey.language != self.language => {
+                monkey.set_language(self.language)?;
+            }
+            Some(_) => (),
+            None => self.monkey = Some(MonkeyType::new(self.language)?),
         }
+        self.audio.configure(config)?;
+        let funboxes = match &self.challenge {
+            Some(challenge) => Funboxes::load(challenge.funbox(), config)?,
+            None => Funboxes::load(&config.funbox, config)?,
+        };
 
-        self.typed_text = Vec::new();
-
-        self.test_text = match &self.mode {
+        let mut quote = None;
+        let text = match &self.mode {
             Mode::Quote { lengths } => {
-                let quote = self.monkey.random_quote(lengths)?;
-                quote.text.clone()
+                let monkey = self.monkey.as_ref().expect("loaded above");
+                let random = monkey.random_quote(lengths)?;
+                quote = Some((random.id, random.length.clone()));
+                random.text.clone()
             }
             Mode::Words { word_count, punctuation, numbers } => {
-                if let Some(words) = self.monkey.random_words(word_count, *punctuation, *numbers) {
-                    words
-                        .iter()
-                        .map(|word| word.to_string())
-                        .collect::<Vec<String>>()
-                        .join(" ")
-                } else {
-                    return Err(crate::Error::NoWordsForLanguage(self.language));
-                }
+                self.random_words(word_count, *punctuation, *numbers)?
+            }
+            Mode::Time { punctuation, numbers, .. } => {
+                let count = WordCount::Custom(TIME_MODE_WORDS);
+                self.random_words(&count, *punctuation, *numbers)?
+            }
+            Mode::Custom { words, order, word_count } => {
+                order.arrange(words, *word_count).join(" ")
             }
-            _ => todo!(),
         };
 
+        if self.is_in_progress() {
+            let result = self.result(ResultStatus::Aborted);
+            self.results.push(result);
+            self.key_stats.merge(&self.statistics.key_stats());
+        }
+
+        self.typed_text = Vec::new();
+        self.was_typed_wrong = HashSet::new();
+        self.replay = None;
+        self.finished = false;
+        self.quote = quote;
+        self.last_result = None;
+        self.last_key_stats = KeyStats::default();
+        self.personal_best = None;
+        self.last_key = None;
+        self.challenge_failures = None;
+        self.funboxes = funboxes;
+        self.test_text = self.funboxes.transform(&text);
+
         self.statistics.reset();
+        self.keystrokes = KeystrokeLog::new(self.test_text.clone());
 
         Ok(())
     }
 
+    fn random_words(
+        &self,
+        count: &WordCount,
+        punctuation: bool,
+        numbers: bool,
+    ) -> crate::Result<String> {
+        let words = self
+            .monkey
+            .as_ref()
+            .and_then(|monkey| monkey.random_words(count, punctuation, numbers));
+        match words {
+            Some(words) => Ok(words
+                .iter()
+                .map(|word| word.to_string())
+                .collect::<Vec<String>>()
+                .join(" ")),
+            None => Err(crate::Error::NoWordsForLanguage(self.language)),
+        }
+    }
+
+    /// Adds words to the text of a time mode test before the caret reaches its end.
+    fn extend_time_mode_text(&mut self) -> crate::Result<()> {
+        let Mode::Time { punctuation, numbers, .. } = self.mode else {
+            return Ok(());
+        };
+        let remaining = self
+            .test_text
+            .chars()
+            .count()
+            .saturating_sub(self.typed_text.len());
+        if remaining < 100 {
+            let count = WordCount::Custom(TIME_MODE_WORDS);
+            let words = self.random_words(&count, punctuation, numbers)?;
+            let text = self.funboxes.transform(&format!(" {words}"));
+            self.test_text.push_str(&text);
+            self.keystrokes.push_text(&text);
+        }
+        Ok(())
+    }
+
+    /// Ends time mode tests when the time is up and lets the funboxes update. Has to be called
+    /// regularly, not only when a key is pressed.
+    pub fn tick(&mut self) {
+        if self.finished || !self.statistics.is_started() {
+            return;
+        }
+
+        let progress = self.progress();
+        self.funboxes.tick(progress);
+
+        if let Mode::Time { seconds, .. } = &self.mode {
+            let seconds_left = seconds.as_usize() as f64 - self.statistics.duration().as_secs_f64();
+            self.audio.time_left(seconds_left);
+            if seconds_left <= 0.0 {
+                self.finish(ResultStatus::Completed);
+            }
+        }
+    }
+
+    /// How far the test is, from 0 to 1. Time mode goes by the time, the other modes by the
+    /// number of typed chars, since funboxes can remove the spaces between words.
+    fn progress(&self) -> f64 {
+        match &self.mode {
+            Mode::Time { seconds, .. } => {
+                self.statistics.duration().as_secs_f64() / seconds.as_usize() as f64
+            }
+            Mode::Words { .. } | Mode::Quote { .. } | Mode::Custom { .. } => {
+                self.typed_text.len() as f64 / self.test_text.chars().count().max(1) as f64
+            }
+        }
+    }
+
+    /// The layout keys are typed in, if the selected layout is emulated.
+    fn emulated_layout<'a>(&'a self, config: &'a crate::Config) -> Option<&'a KeyboardLayout> {
+        match self.funboxes.layout() {
+            Some(layout) => Some(layout),
+            None if config.emulate_layout => Some(&config.layout),
+            None => None,
+        }
+    }
+
     pub fn mode(mut self, mode: Mode) -> Self {
         self.mode = mode;
         self
@@ -87,27 +269,314 @@ impl TestState {
         self.language = language;
     }
 
-    pub fn handle_key_event(&mut self, key_event: KeyEvent) -> crate::Result<()> {
+    pub fn difficulty(mut self, difficulty: Difficulty) -> Self {
+        self.difficulty = difficulty;
+        self
+    }
+
+    pub fn set_difficulty(&mut self, difficulty: Difficulty) {
+        self.difficulty = difficulty;
+    }
+
+    /// Starts a new test with the mode, language and difficulty of `config`. A running challenge
+    /// keeps its own settings. If the test can't be started, the previous settings are kept.
+    pub fn apply_config(&mut self, config: &crate::Config) -> crate::Result<()> {
+        let previous = self.settings();
+        self.set_config(config);
+        self.new_test_or_restore(config, previous)
+    }
+
+    fn settings(&self) -> Settings {
+        (
+            self.mode.clone(),
+            self.language,
+            self.difficulty,
+            self.challenge.clone(),
+        )
+    }
+
+    /// Starts a new test, or goes back to the `previous` settings if it can't be started.
+    fn new_test_or_restore(
+        &mut self,
+        config: &crate::Config,
+        previous: Settings,
+    ) -> crate::Result<()> {
+        let result = self.new_test(config);
+        if result.is_err() {
+            (self.mode, self.language, self.difficulty, self.challenge) = previous;
+        }
+        result
+    }
+
+    /// Uses the mode, language and difficulty of `config` from the next test on.
+    pub fn set_config(&mut self, config: &crate::Config) {
+        if self.challenge.is_none() {
+            self.mode = config.test_mode();
+            self.language = config.language;
+            self.difficulty = config.difficulty;
+        }
+    }
+
+    /// Checks that the sounds and funboxes of `config` can be loaded, without changing the
+    /// current test.
+    pub fn check_config(config: &crate::Config) -> crate::Result<()> {
+        Audio::new(Box::new(NullSink)).configure(config)?;
+        Funboxes::load(&config.funbox, config)?;
+        Ok(())
+    }
+
+    /// Whether a test was started and isn't finished yet.
+    pub fn is_in_progress(&self) -> bool {
+        self.statistics.is_started() && !self.finished
+    }
+
+    /// Starts a test with the settings of `challenge`, including the language, punctuation and
+    /// numbers its requirements ask for. Following tests use them as well, until the challenge
+    /// is stopped.
+    pub fn start_challenge(
+        &mut self,
+        challenge: Challenge,
+        config: &crate::Config,
+    ) -> crate::Result<()> {
+        let mode = challenge.mode()?;
+        let language = challenge.language()?;
+        let previous = self.settings();
+        self.mode = mode;
+        if let Some(language) = language {
+            self.language = language;
+        }
+        self.difficulty = challenge.difficulty();
+        self.challenge = Some(challenge);
+        self.new_test_or_restore(config, previous)
+    }
+
+    /// Goes back to the settings and the funboxes of the config.
+    pub fn stop_challenge(&mut self, config: &crate::Config) -> crate::Result<()> {
+        let previous = self.settings();
+        self.challenge = None;
+        self.set_config(config);
+        self.new_test_or_restore(config, previous)
+    }
+
+    pub fn challenge(&self) -> Option<&Challenge> {
+        self.challenge.as_ref()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Returns the results of all tests that were finished or aborted since the last call.
+    pub fn take_results(&mut self) -> Vec<TestResult> {
+        std::mem::take(&mut self.results)
+    }
+
+    /// Returns the challenges completed by the tests returned by [`Self::take_results`], with
+    /// the timestamp of the result that completed them.
+    pub fn take_completed_challenges(&mut self) -> Vec<(String, u64)> {
+        std::mem::take(&mut self.completed_challenges)
+    }
+
+    /// Returns the key statistics of the tests returned by [`Self::take_results`].
+    pub fn take_key_stats(&mut self) -> KeyStats {
+        std::mem::take(&mut self.key_stats)
+    }
+
+    fn finish(&mut self, status: ResultStatus) {
+        self.statistics.end();
+        self.finished = true;
+        self.funboxes.finish();
+        let result = self.result(status);
+        self.challenge_failures = self
+            .challenge
+            .as_ref()
+            .map(|challenge| challenge.failures(&result));
+        if let (Some(challenge), Some(failures)) = (&self.challenge, &self.challenge_failures)
+            && failures.is_empty()
+        {
+            self.completed_challenges
+                .push((challenge.name.clone(), result.timestamp));
+        }
+        self.last_result = Some(result.clone());
+        self.results.push(result);
+        self.last_key_stats = self.statistics.key_stats();
+        self.key_stats.merge(&self.last_key_stats);
+    }
+
+    /// Sets the comparison with the personal best shown on the results screen.
+    pub fn set_personal_best(&mut self, comparison: PbComparison) {
+        self.personal_best = Some(comparison);
+    }
+
+    fn result(&self, status: ResultStatus) -> TestResult {
+        let (length, punctuation, numbers) = match &self.mode {
+            Mode::Time { seconds, punctuation, numbers } => {
+                (seconds.as_usize(), *punctuation, *numbers)
+            }
+            Mode::Words { word_count, punctuation, numbers } => {
+                (word_count.as_usize(), *punctuation, *numbers)
+            }
+            Mode::Quote { .. } => {
+                let length = self.quote.as_ref().map(|(_, l)| l.clone() as usize);
+                (length.unwrap_or_default(), false, false)
+            }
+            Mode::Custom { word_count, .. } => (*word_count, false, false),
+        };
+
+        let mut result = TestResult {
+            timestamp: self.keystrokes.timestamp(),
+            mode: ModeKind::from(&self.mode),
+            length,
+            quote_id: self.quote.as_ref().map(|(id, _)| *id),
+            language: self.language,
+            punctuation,
+            numbers,
+            difficulty: self.difficulty,
+            wpm: self.statistics.cpm() as f32 / crate::CHARS_PER_WORD,
+            raw_wpm: self.statistics.raw_cpm() as f32 / crate::CHARS_PER_WORD,
+            accuracy: self.statistics.accuracy(),
+            consistency: self.statistics.consistency(),
+            chars: self.char_counts(),
+            duration: self.statistics.duration().as_secs_f64(),
+            status,
+            tags: Vec::new(),
+            funbox: Vec::new(),
+            layouts: Vec::new(),
+        };
+        self.funboxes.result(&mut result);
+        result
+    }
+
+    fn char_counts(&self) -> CharCounts {
+        let mut counts = CharCounts::default();
+        let mut test_text = self.test_text.chars();
+
+        for typed in &self.typed_text {
+            match test_text.next() {
+                Some(actual) if actual == *typed => counts.correct += 1,
+                Some(_) => counts.incorrect += 1,
+                None => counts.extra += 1,
+            }
+        }
+        // Time mode tests end with text that was never meant to be typed.
+        if !matches!(self.mode, Mode::Time { .. }) {
+            counts.missed = test_text.count();
+        }
+
+        counts
+    }
+
+    /// Whether the word the caret is currently in contains a mistake.
+    fn current_word_has_error(&self) -> bool {
+        let pairs: Vec<(char, char)> = self
+            .test_text
+            .chars()
+            .zip(self.typed_text.iter().copied())
+            .collect();
+        pairs
+            .iter()
+            .rev()
+            .take_while(|(actual, _)| *actual != ' ')
+            .any(|(actual, typed)| actual != typed)
+    }
+
+    pub fn keystrokes(&self) -> &KeystrokeLog {
+        &self.keystrokes
+    }
+
+    pub fn replay(&mut self, log: KeystrokeLog) {
+        self.replay = Some(Replay::new(log));
+    }
+
+    pub fn handle_key_event(
+        &mut self,
+        key_event: KeyEvent,
+        config: &crate::Config,
+    ) -> crate::Result<()> {
+        if let Some(replay) = &mut self.replay {
+            if key_event.kind == KeyEventKind::Press {
+                match key_event.code {
+                    KeyCode::Char('1') => replay.set_speed(ReplaySpeed::X1),
+                    KeyCode::Char('2') => replay.set_speed(ReplaySpeed::X2),
+                    KeyCode::Char('4') => replay.set_speed(ReplaySpeed::X4),
+                    KeyCode::Char('r') => replay.restart(),
+                    KeyCode::Char('q') => self.replay = None,
+                    KeyCode::Tab => self.new_test(config)?,
+                    _ => (),
+                }
+            }
+            return Ok(());
+        }
+
         match key_event.kind {
             KeyEventKind::Press => match key_event.modifiers {
+                KeyModifiers::CONTROL => match key_event.code {
+                    KeyCode::Char('r') if self.finished => {
+                        self.replay(self.keystrokes.clone());
+                    }
+                    _ => (),
+                },
                 KeyModifiers::SHIFT | KeyModifiers::NONE => match key_event.code {
+                    KeyCode::Char('h') if self.finished => self.heatmap = self.heatmap.next(),
+                    KeyCode::Char(_) if self.finished => (),
                     KeyCode::Char(c) => {
+                        let c = match self.emulated_layout(config) {
+                            Some(layout) => layout.emulate(c),
+                            None => c,
+                        };
+                        let Some(c) = self.funboxes.filter_input(c) else {
+                            return Ok(());
+                        };
                         let current_index = self.typed_text.len();
-                        if let Some(actual_c) = self.test_text.chars().nth(current_index) {
+                        let expected = self.test_text.chars().nth(current_index);
+                        if let Some(mistake) = Mistake::new(c, expected) {
+                            self.effects.trigger(config.error_feedback.get(mistake));
+                        }
+                        if let Some(actual_c) = expected {
+                            if self.difficulty == Difficulty::Expert
+                                && actual_c == ' '
+                                && self.current_word_has_error()
+                            {
+                                self.finish(ResultStatus::Failed);
+                                return Ok(());
+                            }
                             self.statistics.new_char(current_index, c, actual_c);
+                            self.last_key = Some((c, c == actual_c, Instant::now()));
+                            self.funboxes.keystroke(c == actual_c);
                             if c != actual_c {
+                                self.audio.error();
                                 self.was_typed_wrong.insert(current_index);
+                                self.keystrokes.record(current_index, Some(c), Outcome::Incorrect);
+                            } else {
+                                self.audio.click();
+                                self.keystrokes.record(current_index, Some(c), Outcome::Correct);
                             }
-                        }
-                        if current_index == self.test_text.len() {
-                            self.statistics.end();
+                        } else {
+                            self.audio.error();
+                            self.keystrokes.record(current_index, Some(c), Outcome::Extra);
                         }
                         self.typed_text.push(c);
+                        let failed_master = self.difficulty == Difficulty::Master
+                            && self.test_text.chars().nth(current_index) != Some(c);
+                        if failed_master
+                            || !self.funboxes.validate(&self.test_text, &self.typed_text)
+                        {
+                            self.finish(ResultStatus::Failed);
+                        } else if self.typed_text.len() >= self.test_text.chars().count() {
+                            self.finish(ResultStatus::Completed);
+                        } else {
+                            self.extend_time_mode_text()?;
+                            self.tick();
+                        }
                     }
-                    KeyCode::Backspace => {
-                        let _ = self.typed_text.pop();
+                    KeyCode::Backspace if self.finished => (),
+                    KeyCode::Backspace if !self.typed_text.is_empty() => {
+                        self.audio.click();
+                        self.typed_text.pop();
+                        self.keystrokes
+                            .record(self.typed_text.len(), None, Outcome::Backspace);
                     }
-                    KeyCode::Tab => self.new_test()?,
+                    KeyCode::Tab => self.new_test(config)?,
                     _ => (),
                 },
                 _ => (),
@@ -119,48 +588,347 @@ impl TestState {
 
     pub fn render_options(&self, style: &crate::Style, area: Rect, buf: &mut Buffer) {}
 
-    pub fn render(&self, style: &crate::Style, area: Rect, buf: &mut Buffer) {
-        if self.typed_text.len() >= self.test_text.len() {
-            self.statistics.render_end(area, buf);
+    pub fn render(&self, config: &crate::Config, area: Rect, buf: &mut Buffer) {
+        let style = &config.style;
+        if let Some(replay) = &self.replay {
+            replay.render(style, area, buf);
+        } else if self.finished {
+            let summary_text = self.summary(style);
+            let [summary, keys, keyboard, chart] = Layout::vertical([
+                Constraint::Length(summary_text.height() as u16 + 1),
+                Constraint::Length(6),
+                Constraint::Length(7),
+                Constraint::Fill(1),
+            ])
+            .areas(area);
+
+            summary_text.render(summary, buf);
+            self.last_key_stats.render(MIN_KEY_SAMPLES, style, keys, buf);
+            self.render_heatmap(config, keyboard, buf);
+            self.statistics.render_end(chart, buf);
         } else {
             let [statistics, body] =
                 Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(area);
 
+            let [statistics, info] =
+                Layout::horizontal([Constraint::Length(12), Constraint::Fill(1)]).areas(statistics);
             self.statistics.render(statistics, buf);
+            self.render_info(style, info, buf);
+
+            render_text(
+                &self.test_text,
+                &self.typed_text,
+                &self.was_typed_wrong,
+                &self.funboxes,
+                style,
+                body,
+                buf,
+            );
+            self.effects.render(style, body, buf);
+        }
+    }
+}
 
-            let mut text = Vec::with_capacity(self.test_text.len());
-            let typed_text_len = self.typed_text.len();
+impl TestState {
+    /// Time left in time mode and whatever the funboxes show.
+    fn render_info(&self, style: &crate::Style, area: Rect, buf: &mut Buffer) {
+        let mut line = Line::default();
 
-            for (i, c) in self.test_text.chars().enumerate() {
-                let color = {
-                    if i < typed_text_len {
-                        if c == self.typed_text[i] {
-                            if self.was_typed_wrong.contains(&i) {
-                                Style::new().underlined().underline_color(style.theme.error_extra)
-                            } else {
-                                Style::new().fg(style.theme.text)
-                            }
+        if let Mode::Time { seconds, .. } = &self.mode {
+            let elapsed = self.statistics.duration().as_secs();
+            let left = (seconds.as_usize() as u64).saturating_sub(elapsed);
+            line.push_span(Span::styled(format!("{left}s "), Style::new().fg(style.theme.main)));
+        }
+
+        if let Some(challenge) = &self.challenge {
+            line.push_span(Span::styled(
+                format!("{} ", challenge.display),
+                Style::new().fg(style.theme.text),
+            ));
+        }
+
+        for span in self.funboxes.render_info(style) {
+            line.push_span(span);
+            line.push_span(" ");
+        }
+
+        line.render(area, buf);
+    }
+
+    /// Draws the layout with the next key highlighted and the last pressed key flashing.
+    pub fn render_keymap(&self, config: &crate::Config, area: Rect, buf: &mut Buffer) {
+        if self.replay.is_some() || self.finished {
+            return;
+        }
+
+        let keyboard_layout = self.emulated_layout(config).unwrap_or(&config.layout);
+        let theme = &config.style.theme;
+        let top_row = keyboard_layout.show_top_row
+            || self
+                .test_text
+                .chars()
+                .any(|c| keyboard_layout.find(c).is_some_and(|p| p.row == 0));
+        let mut keyboard = Keyboard::new(keyboard_layout, &config.style).top_row(top_row);
+
+        if let Some((c, correct, at)) = self.last_key
+            && at.elapsed() < KEY_FLASH
+            && let Some(pressed) = keyboard_layout.find(c)
+        {
+            let style = if correct {
+                Style::new().fg(theme.main).bg(theme.sub_alt).bold()
+            } else {
+                Style::new().fg(theme.bg).bg(theme.error)
+            };
+            keyboard = keyboard.key_style(pressed.row, pressed.col, style);
+        }
+
+        if let Some(next) = self.test_text.chars().nth(self.typed_text.len())
+            && let Some(position) = keyboard_layout.find(next)
+        {
+            let style = Style::new().fg(theme.bg).bg(theme.main);
+            keyboard = keyboard.key_style(position.row, position.col, style);
+            if position.shifted {
+                keyboard = keyboard.shift_style(style);
+            }
+        }
+
+        let [area] = Layout::vertical([Constraint::Length(keyboard.height())])
+            .flex(layout::Flex::Center)
+            .areas(area);
+        keyboard.render(area, buf);
+    }
+
+    fn render_heatmap(&self, config: &crate::Config, area: Rect, buf: &mut Buffer) {
+        let style = &config.style;
+        let [title, keyboard, _] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(5),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+
+        Line::from_iter([
+            Span::styled("[h] ", Style::new().fg(style.theme.main)),
+            Span::styled(
+                format!("{} on {}", self.heatmap, config.layout.name),
+                Style::new().fg(style.theme.sub),
+            ),
+        ])
+        .render(title, buf);
+        Keyboard::new(&config.layout, style)
+            .heatmap(&self.last_key_stats, self.heatmap, 1)
+            .render(keyboard, buf);
+    }
+
+    /// The results of the last test, the WPM per layout and whether the challenge was
+    /// completed.
+    fn summary(&self, style: &crate::Style) -> Text<'_> {
+        let Some(result) = &self.last_result else {
+            return Text::default();
+        };
+
+        let mut line = Line::from_iter([
+            Span::styled(
+                format!("{:.0} wpm", result.wpm),
+                Style::new().fg(style.theme.main).bold(),
+            ),
+            Span::styled(
+                format!(
+                    "  {:.0} raw  {:.2}% acc  {:.0}% consistency  {:.1}s",
+                    result.raw_wpm, result.accuracy, result.consistency, result.duration
+                ),
+                Style::new().fg(style.theme.sub),
+            ),
+        ]);
+
+        if result.status == ResultStatus::Failed {
+            line.push_span(Span::styled("  failed", Style::new().fg(style.theme.error).bold()));
+        }
+
+        if let Some(pb) = &self.personal_best {
+            let delta = pb.delta().map(|d| format!(" ({d:+.2})")).unwrap_or_default();
+            if pb.is_new() {
+                line.push_span(Span::styled(
+                    format!("  new PB{delta}"),
+                    Style::new().fg(style.theme.main).bold(),
+                ));
+            } else {
+                line.push_span(Span::styled(
+                    format!("  PB {:.0}{delta}", pb.previous_best.unwrap_or_default()),
+                    Style::new().fg(style.theme.sub),
+                ));
+            }
+        }
+
+        let mut lines = vec![line];
+        if !result.layouts.is_empty() {
+            lines.push(Line::from_iter(result.layouts.iter().map(|layout| {
+                Span::styled(
+                    format!("{} {:.0} wpm  ", layout.layout, layout.wpm),
+                    Style::new().fg(style.theme.sub),
+                )
+            })));
+        }
+
+        if let (Some(challenge), Some(failures)) = (&self.challenge, &self.challenge_failures) {
+            let (status, color) = if failures.is_empty() {
+                ("passed".to_string(), style.theme.main)
+            } else {
+                (format!("failed: {}", failures.join(", ")), style.theme.error)
+            };
+            lines.push(Line::from_iter([
+                Span::styled(format!("{} ", challenge.display), Style::new().fg(style.theme.text)),
+                Span::styled(status, Style::new().fg(color).bold()),
+            ]));
+            if let Some(message) = &challenge.message {
+                lines.push(Line::styled(message.as_str(), Style::new().fg(style.theme.sub)));
+            }
+        }
+
+        Text::from(lines)
+    }
+}
+
+pub fn render_text(
+    test_text: &str,
+    typed_text: &[char],
+    was_typed_wrong: &HashSet<usize>,
+    funboxes: &Funboxes,
+    style: &crate::Style,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let typed_text_len = typed_text.len();
+    let mut chars: Vec<TextChar> = test_text
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let color = {
+                if i < typed_text_len {
+                    if c == typed_text[i] {
+                        if was_typed_wrong.contains(&i) {
+                            Style::new().underlined().underline_color(style.theme.error_extra)
                         } else {
-                            Style::new().fg(style.theme.error)
+                            Style::new().fg(style.theme.text)
                         }
-                    } else if i == typed_text_len {
-                        Style::new().fg(style.theme.untyped_letter).bg(style.theme.caret)
                     } else {
-                        Style::new().fg(style.theme.untyped_letter)
+                        Style::new().fg(style.theme.error)
                     }
-                };
-                if c == ' ' {
-                    text.push(Span::styled("·", color));
+                } else if i == typed_text_len {
+                    let untyped = Style::new().fg(style.theme.untyped_letter);
+                    style.caret.apply(untyped, &style.theme)
                 } else {
-                    text.push(Span::styled(c.to_string(), color));
+                    Style::new().fg(style.theme.untyped_letter)
                 }
+            };
+            TextChar {
+                index: i,
+                char: c,
+                style: color,
             }
+        })
+        .collect();
+    funboxes.render_chars(&mut chars, typed_text_len, style);
+
+    // The line before the caret's line stays visible.
+    let lines = wrap(&chars, area.width);
+    let caret_line = lines
+        .iter()
+        .position(|line| chars[line.clone()].iter().any(|c| c.index == typed_text_len))
+        .unwrap_or(lines.len().saturating_sub(1));
+    let mut lines: Vec<Line<'static>> = lines[caret_line.saturating_sub(1)..]
+        .iter()
+        .take(area.height as usize)
+        .map(|line| {
+            Line::from_iter(chars[line.clone()].iter().map(|c| {
+                let text = if c.char == ' ' { "·".to_string() } else { c.char.to_string() };
+                Span::styled(text, c.style)
+            }))
+        })
+        .collect();
+    funboxes.render_lines(&mut lines);
 
-            let text = Line::from_iter(text);
+    Paragraph::new(lines).render(area, buf);
+}
+
+/// Wraps `chars` at spaces into lines that fit into `width`, keeping the spaces at the end of
+/// the lines. Words wider than `width` are broken where the line is full. Returns the range of
+/// every line in `chars`.
+fn wrap(chars: &[TextChar], width: u16) -> Vec<Range<usize>> {
+    let width = width as usize;
+    let mut lines = Vec::new();
+    let (mut start, mut column) = (0, 0);
 
-            Paragraph::new(text)
-                .wrap(Wrap { trim: true })
-                .render(body, buf);
+    for word in chars.split_inclusive(|c| c.char == ' ') {
+        let trailing_space = usize::from(word.last().is_some_and(|c| c.char == ' '));
+        let mut rest = word.len() - trailing_space;
+        if column > 0 && column + rest > width {
+            lines.push(start..start + column);
+            start += column;
+            column = 0;
+        }
+        while width > 0 && rest > width {
+            lines.push(start..start + width);
+            start += width;
+            rest -= width;
         }
+        column += rest + trailing_space;
+    }
+    if column > 0 {
+        lines.push(start..start + column);
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrap_at_spaces() {
+        let chars: Vec<TextChar> = "ab cde f ghijk"
+            .chars()
+            .enumerate()
+            .map(|(index, char)| TextChar {
+                index,
+                char,
+                style: Style::new(),
+            })
+            .collect();
+
+        // Trailing spaces don't count towards the width.
+        assert_eq!(wrap(&chars, 5), [0..3, 3..9, 9..14]);
+        assert_eq!(wrap(&chars, 8), [0..9, 9..14]);
+        assert!(wrap(&[], 8).is_empty());
+    }
+
+    #[test]
+    fn check_config() {
+        let mut config = crate::Config::default();
+        assert!(TestState::check_config(&config).is_ok());
+
+        config.funbox = vec!["typo".to_string()];
+        assert!(TestState::check_config(&config).is_err());
+
+        config.funbox.clear();
+        config.click_sound = Some("click99".to_string());
+        assert!(TestState::check_config(&config).is_err());
+    }
+
+    #[test]
+    fn wrap_long_words() {
+        let chars: Vec<TextChar> = "ab cdefghijk l"
+            .chars()
+            .enumerate()
+            .map(|(index, char)| TextChar {
+                index,
+                char,
+                style: Style::new(),
+            })
+            .collect();
+
+        assert_eq!(wrap(&chars, 4), [0..3, 3..7, 7..11, 11..14]);
+        assert_eq!(wrap(&chars[3..12], 3), [0..3, 3..6, 6..9]);
     }
 }
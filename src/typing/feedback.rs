@@ -0,0 +1,140 @@
+//! Feedback on mistakes that the terminal can give by itself: the bell, a flash of the typing
+//! area in the theme's error color and a short horizontal jitter of the caret's line.
+
+use ratatui::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+const FLASH_DURATION: Duration = Duration::from_millis(100);
+/// About one frame.
+const SHAKE_DURATION: Duration = Duration::from_millis(50);
+const SHAKE_OFFSET: u16 = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mistake {
+    WrongLetter,
+    /// A letter typed where the word already ended.
+    ExtraLetter,
+    /// A space typed before the word ended.
+    SkippedWord,
+}
+
+impl Mistake {
+    /// The mistake made by typing `typed` where `expected` is, if any. `None` is past the end of
+    /// the text.
+    pub fn new(typed: char, expected: Option<char>) -> Option<Self> {
+        match expected {
+            Some(expected) if expected == typed => None,
+            Some(_) if typed == ' ' => Some(Self::SkippedWord),
+            Some(' ') | None => Some(Self::ExtraLetter),
+            Some(_) => Some(Self::WrongLetter),
+        }
+    }
+}
+
+/// What happens on a mistake.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Feedback {
+    /// Rings the terminal bell.
+    #[serde(default)]
+    pub bell: bool,
+    /// Fills the typing area with the error color.
+    #[serde(default)]
+    pub flash: bool,
+    /// Moves the caret's line to the side for a moment.
+    #[serde(default)]
+    pub shake: bool,
+}
+
+/// The feedback for every kind of mistake.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ErrorFeedback {
+    #[serde(default)]
+    pub wrong_letter: Feedback,
+    #[serde(default)]
+    pub extra_letter: Feedback,
+    #[serde(default)]
+    pub skipped_word: Feedback,
+}
+
+impl ErrorFeedback {
+    pub fn get(&self, mistake: Mistake) -> Feedback {
+        match mistake {
+            Mistake::WrongLetter => self.wrong_letter,
+            Mistake::ExtraLetter => self.extra_letter,
+            Mistake::SkippedWord => self.skipped_word,
+        }
+    }
+}
+
+/// The flash and the shake that are currently shown.
+#[derive(Default)]
+pub struct Effects {
+    flash_until: Option<Instant>,
+    shake_until: Option<Instant>,
+}
+
+impl Effects {
+    pub fn trigger(&mut self, feedback: Feedback) {
+        let now = Instant::now();
+        if feedback.bell {
+            let mut stdout = std::io::stdout();
+            let _ = stdout.write_all(b"\x07").and_then(|()| stdout.flush());
+        }
+        if feedback.flash {
+            self.flash_until = Some(now + FLASH_DURATION);
+        }
+        if feedback.shake {
+            self.shake_until = Some(now + SHAKE_DURATION);
+        }
+    }
+
+    /// Applies the effects to the typing area after the text was rendered into it.
+    pub fn render(&self, style: &crate::Style, area: Rect, buf: &mut Buffer) {
+        let now = Instant::now();
+        if self.shake_until.is_some_and(|until| now < until) {
+            shake(style, area, buf);
+        }
+        if self.flash_until.is_some_and(|until| now < until) {
+            buf.set_style(area, Style::new().bg(style.theme.error));
+        }
+    }
+}
+
+/// Moves the line with the caret to the right. The line is found by the caret's color, since
+/// funboxes can change the order of the lines, so nothing moves when the caret is turned off.
+fn shake(style: &crate::Style, area: Rect, buf: &mut Buffer) {
+    let caret = style.theme.caret;
+    let Some(y) = area.rows().map(|row| row.y).find(|&y| {
+        area.columns().any(|column| {
+            let cell = &buf[(column.x, y)];
+            cell.bg == caret || cell.underline_color == caret
+        })
+    }) else {
+        return;
+    };
+
+    for x in (area.left()..area.right()).rev() {
+        let cell = match x.checked_sub(SHAKE_OFFSET) {
+            Some(from) if from >= area.left() => buf[(from, y)].clone(),
+            _ => ratatui::buffer::Cell::default(),
+        };
+        buf[(x, y)] = cell;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mistakes() {
+        assert_eq!(Mistake::new('a', Some('a')), None);
+        assert_eq!(Mistake::new('b', Some('a')), Some(Mistake::WrongLetter));
+        assert_eq!(Mistake::new('b', Some(' ')), Some(Mistake::ExtraLetter));
+        assert_eq!(Mistake::new('b', None), Some(Mistake::ExtraLetter));
+        assert_eq!(Mistake::new(' ', Some('a')), Some(Mistake::SkippedWord));
+    }
+}
@@ -0,0 +1,277 @@
+//! Funboxes change a test in some way: the text that is generated, how it is drawn, which keys
+//! are accepted or when the test is failed. Any number of them can be active at once, their
+//! hooks are applied in the order they were enabled. They are loaded again for every test, so
+//! they can keep state for a single test.
+
+pub mod layoutfluid;
+pub mod text;
+pub mod visual;
+
+pub use layoutfluid::Layoutfluid;
+
+use crate::monkeytype::KeyboardLayout;
+use crate::user::stats::TestResult;
+
+use ratatui::prelude::*;
+
+/// Names of all funboxes, as monkeytype calls them.
+pub const NAMES: [&str; 11] = [
+    "backwards",
+    "capitals",
+    "ddoouubblleedd",
+    "layoutfluid",
+    "mirror",
+    "nospace",
+    "rAnDoMcAsE",
+    "read_ahead",
+    "read_ahead_easy",
+    "read_ahead_hard",
+    "upside_down",
+];
+
+/// A char of the test text while it is drawn.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextChar {
+    /// Index of the char in the test text.
+    pub index: usize,
+    pub char: char,
+    pub style: Style,
+}
+
+/// Every hook does nothing by default, so funboxes only implement what they change.
+pub trait Funbox {
+    fn name(&self) -> &'static str;
+
+    /// Changes a word of the test text.
+    fn transform_word(&self, word: &str) -> String {
+        word.to_string()
+    }
+
+    /// Changes the test text after the words were transformed and joined. Tests that grow while
+    /// they are typed call this for every chunk of text that is added.
+    fn transform_text(&self, text: String) -> String {
+        text
+    }
+
+    /// Changes a typed char before it is checked. `None` ignores the key.
+    fn filter_input(&self, char: char) -> Option<char> {
+        Some(char)
+    }
+
+    /// Called for every char that was typed, after it was checked.
+    fn keystroke(&mut self, _correct: bool) {}
+
+    /// Whether the test may go on after the last keystroke. The test fails otherwise.
+    fn validate(&self, _test_text: &str, _typed_text: &[char]) -> bool {
+        true
+    }
+
+    /// Called regularly while a test runs. `progress` goes from 0 at the start to 1 at the end.
+    fn tick(&mut self, _progress: f64) {}
+
+    fn finish(&mut self) {}
+
+    /// Adds to the result of a test.
+    fn result(&self, _result: &mut TestResult) {}
+
+    /// The layout keys are emulated in, if the funbox changes it.
+    fn layout(&self) -> Option<&KeyboardLayout> {
+        None
+    }
+
+    /// Changes the chars of the test text before they are wrapped into lines. `caret` is the
+    /// index of the next char to type.
+    fn render_chars(&self, _chars: &mut Vec<TextChar>, _caret: usize, _style: &crate::Style) {}
+
+    /// Changes the wrapped lines of the test text, with one span per char.
+    fn render_lines(&self, _lines: &mut Vec<Line<'static>>) {}
+
+    /// Shown next to the live statistics.
+    fn render_info(&self, _style: &crate::Style) -> Option<Span<'_>> {
+        None
+    }
+}
+
+/// Loads the funbox called `name`.
+pub fn load(name: &str, config: &crate::Config) -> crate::Result<Box<dyn Funbox>> {
+    let funbox: Box<dyn Funbox> = match name {
+        "backwards" => Box::new(visual::Backwards),
+        "capitals" => Box::new(text::Capitals),
+        "ddoouubblleedd" => Box::new(text::Doubled),
+        "layoutfluid" => Box::new(Layoutfluid::load(&config.layoutfluid_names())?),
+        "mirror" => Box::new(visual::Mirror),
+        "nospace" => Box::new(text::NoSpace),
+        "rAnDoMcAsE" => Box::new(text::RandomCase),
+        "read_ahead" => Box::new(visual::ReadAhead::normal()),
+        "read_ahead_easy" => Box::new(visual::ReadAhead::easy()),
+        "read_ahead_hard" => Box::new(visual::ReadAhead::hard()),
+        "upside_down" => Box::new(visual::UpsideDown),
+        _ => {
+            return Err(crate::Error::Generic(
+                format!("Unknown funbox: {name}").into(),
+            ));
+        }
+    };
+
+    Ok(funbox)
+}
+
+/// The active funboxes, which are applied one after the other.
+#[derive(Default)]
+pub struct Funboxes {
+    funboxes: Vec<Box<dyn Funbox>>,
+}
+
+impl Funboxes {
+    pub fn load(names: &[String], config: &crate::Config) -> crate::Result<Self> {
+        let funboxes = names
+            .iter()
+            .map(|name| load(name, config))
+            .collect::<crate::Result<_>>()?;
+
+        Ok(Self { funboxes })
+    }
+
+    pub fn push(&mut self, funbox: Box<dyn Funbox>) {
+        self.funboxes.push(funbox);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.funboxes.is_empty()
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.funboxes
+            .iter()
+            .map(|funbox| funbox.name().to_string())
+            .collect()
+    }
+
+    /// Transforms every word of `text`, then the whole text.
+    pub fn transform(&self, text: &str) -> String {
+        if self.funboxes.is_empty() {
+            return text.to_string();
+        }
+
+        let words: Vec<String> = text
+            .split(' ')
+            .map(|word| {
+                self.funboxes.iter().fold(word.to_string(), |word, funbox| {
+                    funbox.transform_word(&word)
+                })
+            })
+            .collect();
+        self.funboxes
+            .iter()
+            .fold(words.join(" "), |text, funbox| funbox.transform_text(text))
+    }
+
+    pub fn filter_input(&self, char: char) -> Option<char> {
+        self.funboxes
+            .iter()
+            .try_fold(char, |char, funbox| funbox.filter_input(char))
+    }
+
+    pub fn keystroke(&mut self, correct: bool) {
+        self.funboxes
+            .iter_mut()
+            .for_each(|funbox| funbox.keystroke(correct));
+    }
+
+    pub fn validate(&self, test_text: &str, typed_text: &[char]) -> bool {
+        self.funboxes
+            .iter()
+            .all(|funbox| funbox.validate(test_text, typed_text))
+    }
+
+    pub fn tick(&mut self, progress: f64) {
+        self.funboxes
+            .iter_mut()
+            .for_each(|funbox| funbox.tick(progress));
+    }
+
+    pub fn finish(&mut self) {
+        self.funboxes.iter_mut().for_each(|funbox| funbox.finish());
+    }
+
+    /// Records the names of the funboxes and lets every funbox add to the result.
+    pub fn result(&self, result: &mut TestResult) {
+        result.funbox = self.names();
+        self.funboxes
+            .iter()
+            .for_each(|funbox| funbox.result(result));
+    }
+
+    /// The layout of the last funbox that changes it.
+    pub fn layout(&self) -> Option<&KeyboardLayout> {
+        self.funboxes
+            .iter()
+            .rev()
+            .find_map(|funbox| funbox.layout())
+    }
+
+    pub fn render_chars(&self, chars: &mut Vec<TextChar>, caret: usize, style: &crate::Style) {
+        self.funboxes
+            .iter()
+            .for_each(|funbox| funbox.render_chars(chars, caret, style));
+    }
+
+    pub fn render_lines(&self, lines: &mut Vec<Line<'static>>) {
+        self.funboxes
+            .iter()
+            .for_each(|funbox| funbox.render_lines(lines));
+    }
+
+    pub fn render_info(&self, style: &crate::Style) -> Vec<Span<'_>> {
+        self.funboxes
+            .iter()
+            .filter_map(|funbox| funbox.render_info(style))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fails the test as soon as a char is typed wrong.
+    struct SuddenDeath;
+
+    impl Funbox for SuddenDeath {
+        fn name(&self) -> &'static str {
+            "sudden_death"
+        }
+
+        fn validate(&self, test_text: &str, typed_text: &[char]) -> bool {
+            test_text.chars().zip(typed_text).all(|(a, b)| a == *b)
+        }
+    }
+
+    #[test]
+    fn combine() {
+        let mut funboxes = Funboxes::default();
+        funboxes.push(Box::new(text::Capitals));
+        funboxes.push(Box::new(text::Doubled));
+        funboxes.push(Box::new(text::NoSpace));
+        funboxes.push(Box::new(SuddenDeath));
+
+        assert_eq!(funboxes.transform("ab cd"), "AAbbCCdd");
+        assert_eq!(funboxes.filter_input(' '), None);
+        assert_eq!(funboxes.filter_input('a'), Some('a'));
+        assert!(funboxes.validate("abc", &['a', 'b']));
+        assert!(!funboxes.validate("abc", &['a', 'c']));
+        assert_eq!(
+            funboxes.names(),
+            ["capitals", "ddoouubblleedd", "nospace", "sudden_death"]
+        );
+    }
+
+    #[test]
+    fn load_all() {
+        let config = crate::Config::default();
+        for name in NAMES {
+            assert_eq!(load(name, &config).unwrap().name(), name);
+        }
+        assert!(load("unknown", &config).is_err());
+    }
+}
@@ -0,0 +1,160 @@
+//! The layoutfluid funbox: the test is split into equal segments and every segment is typed in
+//! the next layout, emulated on a QWERTY keyboard.
+
+use super::Funbox;
+use crate::monkeytype::KeyboardLayout;
+use crate::user::stats::{LayoutWpm, TestResult};
+
+use ratatui::prelude::*;
+
+use std::time::{Duration, Instant};
+
+/// The layouts monkeytype cycles through if none were picked.
+pub const DEFAULT_LAYOUTS: [&str; 3] = ["qwerty", "dvorak", "colemak"];
+/// How long a layout switch is announced.
+const ANNOUNCE_DURATION: Duration = Duration::from_secs(2);
+
+#[derive(Default)]
+struct Segment {
+    started: Option<Instant>,
+    ended: Option<Instant>,
+    correct: usize,
+}
+
+pub struct Layoutfluid {
+    layouts: Vec<KeyboardLayout>,
+    current: usize,
+    switched: Option<Instant>,
+    segments: Vec<Segment>,
+}
+
+impl Layoutfluid {
+    pub fn load(names: &[&str]) -> crate::Result<Self> {
+        if names.is_empty() {
+            return Err(crate::Error::Generic(
+                "layoutfluid needs at least one layout".into(),
+            ));
+        }
+        let layouts = names
+            .iter()
+            .map(|name| KeyboardLayout::load(name))
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        Ok(Self {
+            segments: layouts.iter().map(|_| Segment::default()).collect(),
+            layouts,
+            current: 0,
+            switched: None,
+        })
+    }
+
+    fn current_layout(&self) -> &KeyboardLayout {
+        &self.layouts[self.current]
+    }
+
+    /// The name of the layout that was just switched to, while the switch should be announced.
+    pub fn announcement(&self) -> Option<&str> {
+        self.switched
+            .filter(|switched| switched.elapsed() < ANNOUNCE_DURATION)
+            .map(|_| self.current_layout().name.as_str())
+    }
+
+    /// WPM of every layout that was typed in.
+    pub fn wpm(&self) -> Vec<LayoutWpm> {
+        self.layouts
+            .iter()
+            .zip(&self.segments)
+            .filter_map(|(layout, segment)| {
+                let started = segment.started?;
+                let minutes = segment.ended?.duration_since(started).as_secs_f32() / 60.0;
+                let wpm = if minutes > 0.0 {
+                    segment.correct as f32 / crate::CHARS_PER_WORD / minutes
+                } else {
+                    0.0
+                };
+                Some(LayoutWpm {
+                    layout: layout.name.clone(),
+                    wpm,
+                })
+            })
+            .collect()
+    }
+}
+
+impl Funbox for Layoutfluid {
+    fn name(&self) -> &'static str {
+        "layoutfluid"
+    }
+
+    /// Counts a keystroke in the current segment.
+    fn keystroke(&mut self, correct: bool) {
+        let segment = &mut self.segments[self.current];
+        segment.started.get_or_insert_with(Instant::now);
+        if correct {
+            segment.correct += 1;
+        }
+    }
+
+    /// Moves to the segment for `progress`.
+    fn tick(&mut self, progress: f64) {
+        let len = self.layouts.len();
+        let segment = ((progress.max(0.0) * len as f64) as usize).min(len - 1);
+        if segment > self.current {
+            let now = Instant::now();
+            self.segments[self.current].ended = Some(now);
+            self.current = segment;
+            self.segments[segment].started = Some(now);
+            self.switched = Some(now);
+        }
+    }
+
+    fn finish(&mut self) {
+        self.segments[self.current].ended = Some(Instant::now());
+    }
+
+    fn result(&self, result: &mut TestResult) {
+        result.layouts = self.wpm();
+    }
+
+    fn layout(&self) -> Option<&KeyboardLayout> {
+        Some(self.current_layout())
+    }
+
+    fn render_info(&self, style: &crate::Style) -> Option<Span<'_>> {
+        Some(match self.announcement() {
+            Some(name) => Span::styled(
+                format!("--- !!! {name} !!! ---"),
+                Style::new().fg(style.theme.main).bold(),
+            ),
+            None => Span::styled(
+                self.current_layout().name.as_str(),
+                Style::new().fg(style.theme.sub),
+            ),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn segments() {
+        let mut layoutfluid = Layoutfluid::load(&["qwerty", "colemak"]).unwrap();
+        assert_eq!(layoutfluid.current_layout().name, "qwerty");
+
+        layoutfluid.keystroke(true);
+        layoutfluid.tick(0.4);
+        assert_eq!(layoutfluid.current_layout().name, "qwerty");
+        assert_eq!(layoutfluid.announcement(), None);
+
+        layoutfluid.tick(0.5);
+        assert_eq!(layoutfluid.current_layout().name, "colemak");
+        assert_eq!(layoutfluid.announcement(), Some("colemak"));
+        layoutfluid.keystroke(false);
+        layoutfluid.finish();
+
+        let names: Vec<String> = layoutfluid.wpm().into_iter().map(|l| l.layout).collect();
+        assert_eq!(names, ["qwerty", "colemak"]);
+    }
+}
@@ -0,0 +1,74 @@
+//! Funboxes that change the test text.
+
+use super::Funbox;
+
+use rand::Rng;
+
+/// Starts every word with a capital letter.
+pub struct Capitals;
+
+impl Funbox for Capitals {
+    fn name(&self) -> &'static str {
+        "capitals"
+    }
+
+    fn transform_word(&self, word: &str) -> String {
+        let mut chars = word.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => String::new(),
+        }
+    }
+}
+
+/// Types every letter twice.
+pub struct Doubled;
+
+impl Funbox for Doubled {
+    fn name(&self) -> &'static str {
+        "ddoouubblleedd"
+    }
+
+    fn transform_word(&self, word: &str) -> String {
+        word.chars().flat_map(|c| [c, c]).collect()
+    }
+}
+
+/// Removes the spaces from the text and ignores the space bar.
+pub struct NoSpace;
+
+impl Funbox for NoSpace {
+    fn name(&self) -> &'static str {
+        "nospace"
+    }
+
+    fn transform_text(&self, text: String) -> String {
+        text.replace(' ', "")
+    }
+
+    fn filter_input(&self, char: char) -> Option<char> {
+        (char != ' ').then_some(char)
+    }
+}
+
+/// Makes every letter upper or lower case at random.
+pub struct RandomCase;
+
+impl Funbox for RandomCase {
+    fn name(&self) -> &'static str {
+        "rAnDoMcAsE"
+    }
+
+    fn transform_word(&self, word: &str) -> String {
+        let mut rng = rand::rng();
+        word.chars()
+            .flat_map(|c| {
+                if rng.random_bool(0.5) {
+                    c.to_uppercase().collect::<Vec<_>>()
+                } else {
+                    c.to_lowercase().collect()
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,248 @@
+//! Funboxes that only change how the test text is drawn. The text that has to be typed and the
+//! way it is scored stay the same.
+
+use super::{Funbox, TextChar};
+
+use ratatui::prelude::*;
+
+/// Chars that look like each other when mirrored horizontally.
+const MIRRORED: [(char, char); 7] = [
+    ('b', 'd'),
+    ('p', 'q'),
+    ('(', ')'),
+    ('[', ']'),
+    ('{', '}'),
+    ('<', '>'),
+    ('/', '\\'),
+];
+
+/// Chars that look like each other when rotated by 180 degrees.
+const UPSIDE_DOWN: [(char, char); 30] = [
+    ('a', 'ɐ'),
+    ('b', 'q'),
+    ('c', 'ɔ'),
+    ('d', 'p'),
+    ('e', 'ǝ'),
+    ('f', 'ɟ'),
+    ('g', 'ƃ'),
+    ('h', 'ɥ'),
+    ('i', 'ᴉ'),
+    ('j', 'ɾ'),
+    ('k', 'ʞ'),
+    ('m', 'ɯ'),
+    ('n', 'u'),
+    ('r', 'ɹ'),
+    ('t', 'ʇ'),
+    ('v', 'ʌ'),
+    ('w', 'ʍ'),
+    ('y', 'ʎ'),
+    ('A', '∀'),
+    ('E', 'Ǝ'),
+    ('L', '˥'),
+    ('M', 'W'),
+    ('T', '┴'),
+    ('.', '˙'),
+    (',', '\''),
+    ('?', '¿'),
+    ('!', '¡'),
+    ('(', ')'),
+    ('[', ']'),
+    ('{', '}'),
+];
+
+/// Looks `char` up in both directions of `pairs`.
+fn swap(pairs: &[(char, char)], char: char) -> char {
+    pairs
+        .iter()
+        .find_map(|(a, b)| match char {
+            c if c == *a => Some(*b),
+            c if c == *b => Some(*a),
+            _ => None,
+        })
+        .unwrap_or(char)
+}
+
+/// Reverses every line and replaces its chars with `swap`.
+fn flip_lines(lines: &mut [Line<'static>], pairs: &[(char, char)]) {
+    for line in lines {
+        line.spans.reverse();
+        for span in &mut line.spans {
+            let content: String = span.content.chars().rev().map(|c| swap(pairs, c)).collect();
+            span.content = content.into();
+        }
+        line.alignment = Some(Alignment::Right);
+    }
+}
+
+/// Index of the word every char is in, counting the spaces before it.
+fn word_numbers(chars: &[TextChar]) -> Vec<usize> {
+    chars
+        .iter()
+        .scan(0, |word, c| {
+            let number = *word;
+            if c.char == ' ' {
+                *word += 1;
+            }
+            Some(number)
+        })
+        .collect()
+}
+
+/// Draws every word from right to left.
+pub struct Backwards;
+
+impl Funbox for Backwards {
+    fn name(&self) -> &'static str {
+        "backwards"
+    }
+
+    fn render_chars(&self, chars: &mut Vec<TextChar>, _caret: usize, _style: &crate::Style) {
+        for word in chars.split_mut(|c| c.char == ' ') {
+            word.reverse();
+        }
+    }
+}
+
+/// Draws the text as if seen in a mirror.
+pub struct Mirror;
+
+impl Funbox for Mirror {
+    fn name(&self) -> &'static str {
+        "mirror"
+    }
+
+    fn render_lines(&self, lines: &mut Vec<Line<'static>>) {
+        flip_lines(lines, &MIRRORED);
+    }
+}
+
+/// Draws the text rotated by 180 degrees, so the lines are in reverse order as well.
+pub struct UpsideDown;
+
+impl Funbox for UpsideDown {
+    fn name(&self) -> &'static str {
+        "upside_down"
+    }
+
+    fn render_lines(&self, lines: &mut Vec<Line<'static>>) {
+        lines.reverse();
+        flip_lines(lines, &UPSIDE_DOWN);
+    }
+}
+
+/// Hides the untyped letters of the current word and the `words - 1` words after it, so the
+/// text has to be read ahead of the caret.
+pub struct ReadAhead {
+    name: &'static str,
+    words: usize,
+}
+
+impl ReadAhead {
+    pub fn easy() -> Self {
+        Self {
+            name: "read_ahead_easy",
+            words: 1,
+        }
+    }
+
+    pub fn normal() -> Self {
+        Self {
+            name: "read_ahead",
+            words: 2,
+        }
+    }
+
+    pub fn hard() -> Self {
+        Self {
+            name: "read_ahead_hard",
+            words: 3,
+        }
+    }
+}
+
+impl Funbox for ReadAhead {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn render_chars(&self, chars: &mut Vec<TextChar>, caret: usize, style: &crate::Style) {
+        let numbers = word_numbers(chars);
+        let Some(position) = chars.iter().position(|c| c.index == caret) else {
+            return;
+        };
+        // After the last letter of a word the next word is the current one.
+        let first = numbers[position] + usize::from(chars[position].char == ' ');
+        let hidden = first..first + self.words;
+
+        for (c, number) in chars.iter_mut().zip(numbers) {
+            if c.index >= caret && c.char != ' ' && hidden.contains(&number) {
+                let color = if c.index == caret {
+                    style.theme.caret
+                } else {
+                    style.theme.bg
+                };
+                c.style = c.style.fg(color);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(text: &str) -> Vec<TextChar> {
+        text.chars()
+            .enumerate()
+            .map(|(index, char)| TextChar {
+                index,
+                char,
+                style: Style::new(),
+            })
+            .collect()
+    }
+
+    fn text(chars: &[TextChar]) -> String {
+        chars.iter().map(|c| c.char).collect()
+    }
+
+    #[test]
+    fn backwards() {
+        let mut chars = chars("abc de");
+        Backwards.render_chars(&mut chars, 0, &crate::Style::default());
+        assert_eq!(text(&chars), "cba ed");
+        assert_eq!(chars[0].index, 2);
+    }
+
+    #[test]
+    fn flip() {
+        let mut lines = vec![Line::from_iter(["b", "e", "d"].map(Span::raw))];
+        Mirror.render_lines(&mut lines);
+        assert_eq!(lines[0].to_string(), "bed");
+        assert_eq!(lines[0].alignment, Some(Alignment::Right));
+
+        let mut lines = vec![Line::raw("ab"), Line::raw("cd")];
+        UpsideDown.render_lines(&mut lines);
+        assert_eq!(lines[0].to_string(), "pɔ");
+        assert_eq!(lines[1].to_string(), "qɐ");
+    }
+
+    #[test]
+    fn read_ahead() {
+        let style = crate::Style::default();
+        let hidden = |funbox: ReadAhead, caret: usize| {
+            let mut chars = chars("ab cd ef gh");
+            funbox.render_chars(&mut chars, caret, &style);
+            chars
+                .iter()
+                .filter(|c| c.style.fg.is_some())
+                .map(|c| c.char)
+                .collect::<String>()
+        };
+
+        assert_eq!(hidden(ReadAhead::easy(), 1), "b");
+        assert_eq!(hidden(ReadAhead::normal(), 2), "cdef");
+        assert_eq!(hidden(ReadAhead::normal(), 4), "def");
+        assert_eq!(hidden(ReadAhead::hard(), 0), "abcdef");
+    }
+}
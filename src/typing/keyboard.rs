@@ -0,0 +1,294 @@
+use crate::monkeytype::{KeyboardLayout, LayoutType};
+use crate::user::stats::{KeyStat, KeyStats};
+
+use ratatui::prelude::*;
+
+use std::collections::HashMap;
+use std::fmt;
+
+/// Columns a key takes up, including the gap to the next key.
+const KEY_WIDTH: u16 = 4;
+const SPACE_BAR_WIDTH: u16 = 6 * KEY_WIDTH - 1;
+pub const KEYBOARD_WIDTH: u16 = 6 + 13 * KEY_WIDTH;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Heatmap {
+    #[default]
+    Errors,
+    Latency,
+}
+
+impl Heatmap {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Errors => Self::Latency,
+            Self::Latency => Self::Errors,
+        }
+    }
+
+    fn value(&self, stat: &KeyStat) -> Option<f32> {
+        match self {
+            Self::Errors => Some(stat.error_rate()),
+            Self::Latency => stat.mean_latency(),
+        }
+    }
+}
+
+impl fmt::Display for Heatmap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let string = match self {
+            Self::Errors => "error rate",
+            Self::Latency => "latency",
+        };
+
+        write!(f, "{string}")
+    }
+}
+
+/// Draws a [`KeyboardLayout`] with one line per row. Keys are drawn with the theme's `sub_alt`
+/// background unless they were given a style with [`Self::key_style`].
+pub struct Keyboard<'a> {
+    layout: &'a KeyboardLayout,
+    style: &'a crate::Style,
+    top_row: bool,
+    key_styles: HashMap<(usize, usize), Style>,
+    shift_style: Option<Style>,
+}
+
+impl<'a> Keyboard<'a> {
+    pub fn new(layout: &'a KeyboardLayout, style: &'a crate::Style) -> Self {
+        Self {
+            layout,
+            style,
+            top_row: true,
+            key_styles: HashMap::new(),
+            shift_style: None,
+        }
+    }
+
+    pub fn top_row(mut self, top_row: bool) -> Self {
+        self.top_row = top_row;
+        self
+    }
+
+    pub fn key_style(mut self, row: usize, col: usize, style: Style) -> Self {
+        self.key_styles.insert((row, col), style);
+        self
+    }
+
+    /// Style of both shift keys.
+    pub fn shift_style(mut self, style: Style) -> Self {
+        self.shift_style = Some(style);
+        self
+    }
+
+    /// Colors every key by its error rate or latency, from the theme's main color for the best
+    /// key to its error color for the worst. Keys typed less than `min_samples` times keep the
+    /// default style.
+    pub fn heatmap(mut self, key_stats: &KeyStats, heatmap: Heatmap, min_samples: u32) -> Self {
+        let mut values = Vec::new();
+        for (row, keys) in self.layout.rows().iter().enumerate() {
+            for (col, key) in keys.iter().enumerate() {
+                let mut stat = KeyStat::default();
+                for char in key.chars().iter().take(2) {
+                    if let Some(char_stat) = key_stats.key(*char) {
+                        stat.merge(char_stat);
+                    }
+                }
+                if stat.count >= min_samples
+                    && let Some(value) = heatmap.value(&stat)
+                {
+                    values.push(((row, col), value));
+                }
+            }
+        }
+
+        let min = values.iter().map(|(_, v)| *v).fold(f32::INFINITY, f32::min);
+        let max = values
+            .iter()
+            .map(|(_, v)| *v)
+            .fold(f32::NEG_INFINITY, f32::max);
+        for (position, value) in values {
+            let t = if max > min {
+                (value - min) / (max - min)
+            } else {
+                0.0
+            };
+            let color = heat_color(&self.style.theme, t);
+            self.key_styles
+                .insert(position, Style::new().fg(self.style.theme.bg).bg(color));
+        }
+
+        self
+    }
+
+    pub fn height(&self) -> u16 {
+        if self.top_row { 5 } else { 4 }
+    }
+
+    /// Horizontal offset of the first key in `row`, roughly like the stagger of a real
+    /// keyboard.
+    fn row_offset(&self, row: usize) -> u16 {
+        match (row, self.layout.kind) {
+            (0, _) => 0,
+            (1, _) => 6,
+            (2, _) => 7,
+            (3, LayoutType::Ansi) => 9,
+            (3, LayoutType::Iso) => 5,
+            _ => 4 * KEY_WIDTH,
+        }
+    }
+}
+
+impl Widget for Keyboard<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let x = area.x + area.width.saturating_sub(KEYBOARD_WIDTH) / 2;
+        let default_style = Style::new()
+            .fg(self.style.theme.text)
+            .bg(self.style.theme.sub_alt);
+        let first_row = if self.top_row { 0 } else { 1 };
+
+        for (row, keys) in self.layout.rows().iter().enumerate().skip(first_row) {
+            let y = area.y + (row - first_row) as u16;
+            if y >= area.bottom() {
+                break;
+            }
+
+            let mut offset = self.row_offset(row);
+            if row == 4 {
+                // Center the space bar, even if there are other keys next to it.
+                offset = offset.saturating_sub(KEY_WIDTH * keys.len().saturating_sub(1) as u16);
+            }
+
+            // The shift keys fill the space left and right of the bottom letter row.
+            if row == 3 {
+                let style = self.shift_style.unwrap_or(default_style);
+                let left = Rect::new(x, y, offset - 1, 1).intersection(area);
+                Span::styled(format!("{:^1$}", "⇧", left.width as usize), style).render(left, buf);
+                let right_offset = offset + keys.len() as u16 * KEY_WIDTH;
+                let right = Rect::new(
+                    x + right_offset,
+                    y,
+                    KEYBOARD_WIDTH.saturating_sub(right_offset),
+                    1,
+                )
+                .intersection(area);
+                Span::styled(format!("{:^1$}", "⇧", right.width as usize), style)
+                    .render(right, buf);
+            }
+
+            for (col, key) in keys.iter().enumerate() {
+                let label = key.normal().unwrap_or(' ');
+                let is_space_bar = row == 4 && label == ' ';
+                let width = if is_space_bar {
+                    SPACE_BAR_WIDTH
+                } else {
+                    KEY_WIDTH - 1
+                };
+
+                // Unused positions are marked with a space.
+                if label != ' ' || is_space_bar {
+                    let style = self
+                        .key_styles
+                        .get(&(row, col))
+                        .copied()
+                        .unwrap_or(default_style);
+                    let text = format!("{:^1$}", label, width as usize);
+                    let key_area = Rect::new(x + offset, y, width, 1).intersection(area);
+                    Span::styled(text, style).render(key_area, buf);
+                }
+
+                offset += width + 1;
+            }
+        }
+    }
+}
+
+/// Color for `t` between 0 (best) and 1 (worst).
+fn heat_color(theme: &crate::Theme, t: f32) -> Color {
+    match (theme.main, theme.error) {
+        (Color::Rgb(r1, g1, b1), Color::Rgb(r2, g2, b2)) => {
+            let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+            Color::Rgb(mix(r1, r2), mix(g1, g2), mix(b1, b2))
+        }
+        // Named and indexed colors can't be mixed.
+        _ if t < 1.0 / 3.0 => theme.main,
+        _ if t < 2.0 / 3.0 => theme.error_extra,
+        _ => theme.error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monkeytype::layout::tests::layout;
+
+    fn render(keyboard: Keyboard) -> Buffer {
+        let area = Rect::new(0, 0, KEYBOARD_WIDTH, 5);
+        let mut buf = Buffer::empty(area);
+        keyboard.render(area, &mut buf);
+        buf
+    }
+
+    /// Column of the first cell in line `y` showing `symbol`.
+    fn find(buf: &Buffer, y: u16, symbol: &str) -> Option<u16> {
+        (0..buf.area.width).find(|&x| buf[(x, y)].symbol() == symbol)
+    }
+
+    #[test]
+    fn row_offsets() {
+        let style = crate::Config::default().style;
+        for (kind, shift_width) in [(LayoutType::Ansi, 8), (LayoutType::Iso, 4)] {
+            let layout = layout(kind, ["1", "q", "a", "z", " "]);
+            let buf = render(Keyboard::new(&layout, &style));
+
+            assert_eq!(find(&buf, 0, "1"), Some(1));
+            assert_eq!(find(&buf, 1, "q"), Some(7));
+            assert_eq!(find(&buf, 2, "a"), Some(8));
+            // The left shift key fills the row up to the first key.
+            assert_eq!(find(&buf, 3, "z"), Some(shift_width + 2));
+            assert_eq!(buf[(shift_width - 1, 3)].bg, style.theme.sub_alt);
+            assert_eq!(buf[(shift_width, 3)].bg, Color::Reset);
+
+            let space_bar = 4 * KEY_WIDTH;
+            assert_eq!(buf[(space_bar - 1, 4)].bg, Color::Reset);
+            assert_eq!(buf[(space_bar, 4)].bg, style.theme.sub_alt);
+            assert_eq!(buf[(space_bar + SPACE_BAR_WIDTH - 1, 4)].bg, style.theme.sub_alt);
+            assert_eq!(buf[(space_bar + SPACE_BAR_WIDTH, 4)].bg, Color::Reset);
+        }
+    }
+
+    #[test]
+    fn short_space_bar_row() {
+        let style = crate::Config::default().style;
+
+        let empty = layout(LayoutType::Ansi, ["1", "q", "a", "z", ""]);
+        let buf = render(Keyboard::new(&empty, &style));
+        assert!((0..KEYBOARD_WIDTH).all(|x| buf[(x, 4)] == ratatui::buffer::Cell::EMPTY));
+
+        // Keys next to the space bar move it to the left, so that the row stays centered.
+        let two_keys = layout(LayoutType::Ansi, ["1", "q", "a", "z", " x"]);
+        let buf = render(Keyboard::new(&two_keys, &style));
+        let space_bar = 3 * KEY_WIDTH;
+        assert_eq!(buf[(space_bar, 4)].bg, style.theme.sub_alt);
+        assert_eq!(find(&buf, 4, "x"), Some(space_bar + SPACE_BAR_WIDTH + 2));
+    }
+
+    #[test]
+    fn heatmap_with_equal_values() {
+        let style = crate::Config::default().style;
+        let layout = layout(LayoutType::Ansi, ["1", "q", "ab", "z", " "]);
+        let mut key_stats = KeyStats::default();
+        for key in ['a', 'b'] {
+            key_stats.record(None, key, Some(100), false);
+        }
+
+        for heatmap in [Heatmap::Errors, Heatmap::Latency] {
+            let buf = render(Keyboard::new(&layout, &style).heatmap(&key_stats, heatmap, 1));
+            assert_eq!(buf[(8, 2)].bg, style.theme.main);
+            assert_eq!(buf[(12, 2)].bg, style.theme.main);
+            // Keys without samples keep the default style.
+            assert_eq!(buf[(7, 1)].bg, style.theme.sub_alt);
+        }
+    }
+}
@@ -1,6 +1,8 @@
+use rand::seq::{IndexedRandom, SliceRandom};
 use serde::{Deserialize, Serialize, de};
 
 use std::fmt;
+use std::str::FromStr;
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
 pub enum QuoteLength {
@@ -35,6 +37,17 @@ impl From<usize> for QuoteLength {
     }
 }
 
+impl FromStr for QuoteLength {
+    type Err = crate::Error;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        [Self::All, Self::Short, Self::Medium, Self::Long, Self::Thicc]
+            .into_iter()
+            .find(|length| length.to_string() == name)
+            .ok_or_else(|| crate::Error::Generic(format!("Unknown quote length: {name}").into()))
+    }
+}
+
 impl fmt::Display for QuoteLength {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let string = match self {
@@ -49,6 +62,70 @@ impl fmt::Display for QuoteLength {
     }
 }
 
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    /// Classic mode, mistakes are allowed.
+    #[default]
+    Normal,
+    /// Fails the test when a word containing a mistake is submitted.
+    Expert,
+    /// Fails the test on any wrong keystroke.
+    Master,
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let string = match self {
+            Self::Normal => "normal",
+            Self::Expert => "expert",
+            Self::Master => "master",
+        };
+
+        write!(f, "{string}")
+    }
+}
+
+/// How the words of a custom text are arranged.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TextOrder {
+    /// The words in their order, starting over at the end.
+    #[default]
+    Repeat,
+    /// Words picked at random.
+    Random,
+    /// The words shuffled, shuffled again at the end.
+    Shuffle,
+}
+
+impl TextOrder {
+    /// Arranges `count` of `words`.
+    pub fn arrange(&self, words: &[String], count: usize) -> Vec<String> {
+        if words.is_empty() {
+            return Vec::new();
+        }
+
+        let mut rng = rand::rng();
+        match self {
+            Self::Repeat => words.iter().cycle().take(count).cloned().collect(),
+            Self::Random => (0..count)
+                .filter_map(|_| words.choose(&mut rng).cloned())
+                .collect(),
+            Self::Shuffle => {
+                let mut arranged = Vec::with_capacity(count);
+                while arranged.len() < count {
+                    let mut shuffled = words.to_vec();
+                    shuffled.shuffle(&mut rng);
+                    arranged.extend(shuffled.into_iter().take(count - arranged.len()));
+                }
+                arranged
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
 pub enum Seconds {
     S15,
     S30,
@@ -69,12 +146,25 @@ impl Seconds {
     }
 }
 
+impl From<usize> for Seconds {
+    fn from(seconds: usize) -> Self {
+        match seconds {
+            15 => Self::S15,
+            30 => Self::S30,
+            60 => Self::S60,
+            120 => Self::S120,
+            seconds => Self::Custom(seconds),
+        }
+    }
+}
+
 impl fmt::Display for Seconds {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", self.as_usize())
     }
 }
 
+#[derive(Clone, Copy)]
 pub enum WordCount {
     W10,
     W25,
@@ -95,12 +185,25 @@ impl WordCount {
     }
 }
 
+impl From<usize> for WordCount {
+    fn from(words: usize) -> Self {
+        match words {
+            10 => Self::W10,
+            25 => Self::W25,
+            50 => Self::W50,
+            100 => Self::W100,
+            words => Self::Custom(words),
+        }
+    }
+}
+
 impl fmt::Display for WordCount {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", self.as_usize())
     }
 }
 
+#[derive(Clone)]
 pub enum Mode {
     Time {
         seconds: Seconds,
@@ -115,6 +218,12 @@ pub enum Mode {
     Quote {
         lengths: Vec<QuoteLength>,
     },
+    /// A text of `word_count` words arranged from `words`.
+    Custom {
+        words: Vec<String>,
+        order: TextOrder,
+        word_count: usize,
+    },
 }
 
 impl Mode {
@@ -138,6 +247,14 @@ impl Mode {
         Self::Quote { lengths }
     }
 
+    pub fn custom(words: Vec<String>, order: TextOrder, word_count: usize) -> Self {
+        Self::Custom {
+            words,
+            order,
+            word_count,
+        }
+    }
+
     pub fn punctuation(self) -> Self {
         match self {
             Self::Time {
@@ -156,7 +273,7 @@ impl Mode {
                 punctuation: true,
                 numbers,
             },
-            Self::Quote { lengths } => Self::Quote { lengths },
+            mode @ (Self::Quote { .. } | Self::Custom { .. }) => mode,
         }
     }
 
@@ -180,7 +297,7 @@ impl Mode {
                 punctuation,
                 numbers: true,
             },
-            Self::Quote { lengths } => Self::Quote { lengths },
+            mode @ (Self::Quote { .. } | Self::Custom { .. }) => mode,
         }
     }
 }
@@ -0,0 +1,325 @@
+use crate::user::stats::write_atomic;
+
+use serde::{Deserialize, Serialize};
+use ratatui::prelude::*;
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const REPLAYS_DIR: &str = "replays";
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub enum Outcome {
+    #[serde(rename = "c")]
+    Correct,
+    #[serde(rename = "i")]
+    Incorrect,
+    #[serde(rename = "x")]
+    Extra,
+    #[serde(rename = "b")]
+    Backspace,
+}
+
+/// A single key event. `time` is the number of milliseconds since the first keystroke of the
+/// test, measured with a monotonic clock.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Keystroke {
+    #[serde(rename = "t")]
+    pub time: u64,
+    #[serde(rename = "i")]
+    pub index: usize,
+    #[serde(rename = "k", default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<char>,
+    #[serde(rename = "o")]
+    pub outcome: Outcome,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct KeystrokeLog {
+    /// Unix timestamp in milliseconds of the first keystroke, also used as the file name.
+    timestamp: u64,
+    text: String,
+    keystrokes: Vec<Keystroke>,
+    #[serde(skip)]
+    started: Option<Instant>,
+}
+
+impl KeystrokeLog {
+    pub fn new(text: String) -> Self {
+        Self {
+            text,
+            ..Default::default()
+        }
+    }
+
+    pub fn dir() -> PathBuf {
+        crate::dirs::state().join(REPLAYS_DIR)
+    }
+
+    pub fn path(timestamp: u64) -> PathBuf {
+        Self::dir().join(format!("{timestamp}.json"))
+    }
+
+    pub fn load(timestamp: u64) -> crate::Result<Self> {
+        let content = fs::read(Self::path(timestamp))?;
+        Ok(serde_json::from_slice(&content)?)
+    }
+
+    /// Loads every saved log, oldest first. Logs that can't be loaded are skipped and added to
+    /// `errors`.
+    pub fn load_all(errors: &mut Vec<crate::Error>) -> crate::Result<Vec<Self>> {
+        Self::load_dir(&Self::dir(), errors)
+    }
+
+    fn load_dir(dir: &Path, errors: &mut Vec<crate::Error>) -> crate::Result<Vec<Self>> {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut logs = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let log = fs::read(&path)
+                .map_err(crate::Error::from)
+                .and_then(|content| Ok(serde_json::from_slice::<Self>(&content)?));
+            match log {
+                Ok(log) => logs.push(log),
+                Err(err) => errors.push(crate::Error::Generic(
+                    format!("Skipped {}: {err}", path.display()).into(),
+                )),
+            }
+        }
+        logs.sort_by_key(|log| log.timestamp);
+
+        Ok(logs)
+    }
+
+    pub fn save(&self) -> crate::Result<()> {
+        write_atomic(&Self::path(self.timestamp), &serde_json::to_vec(self)?)
+    }
+
+    pub fn record(&mut self, index: usize, key: Option<char>, outcome: Outcome) {
+        let time = match self.started {
+            Some(started) => started.elapsed().as_millis() as u64,
+            None => {
+                self.started = Some(Instant::now());
+                self.timestamp = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_millis() as u64)
+                    .unwrap_or_default();
+                0
+            }
+        };
+
+        self.keystrokes.push(Keystroke {
+            time,
+            index,
+            key,
+            outcome,
+        });
+    }
+
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Appends to the text of tests that grow while they are typed.
+    pub fn push_text(&mut self, text: &str) {
+        self.text.push_str(text);
+    }
+
+    pub fn keystrokes(&self) -> &[Keystroke] {
+        &self.keystrokes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keystrokes.is_empty()
+    }
+
+    pub fn duration(&self) -> Duration {
+        let millis = self.keystrokes.last().map(|k| k.time).unwrap_or_default();
+        Duration::from_millis(millis)
+    }
+
+    /// Returns the typed text and the indexes that were typed wrong at least once, after all
+    /// keystrokes up to `elapsed` were applied.
+    pub fn state_at(&self, elapsed: Duration) -> (Vec<char>, HashSet<usize>) {
+        let elapsed = elapsed.as_millis() as u64;
+        let mut typed = Vec::new();
+        let mut was_typed_wrong = HashSet::new();
+
+        for keystroke in self.keystrokes.iter().take_while(|k| k.time <= elapsed) {
+            match (keystroke.outcome, keystroke.key) {
+                (Outcome::Backspace, _) => {
+                    typed.pop();
+                }
+                (outcome, Some(key)) => {
+                    if outcome == Outcome::Incorrect {
+                        was_typed_wrong.insert(keystroke.index);
+                    }
+                    typed.push(key);
+                }
+                _ => (),
+            }
+        }
+
+        (typed, was_typed_wrong)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReplaySpeed {
+    X1 = 1,
+    X2 = 2,
+    X4 = 4,
+}
+
+pub struct Replay {
+    log: KeystrokeLog,
+    speed: ReplaySpeed,
+    resumed: Instant,
+    offset: Duration,
+}
+
+impl Replay {
+    pub fn new(log: KeystrokeLog) -> Self {
+        Self {
+            log,
+            speed: ReplaySpeed::X1,
+            resumed: Instant::now(),
+            offset: Duration::ZERO,
+        }
+    }
+
+    pub fn speed(&self) -> ReplaySpeed {
+        self.speed
+    }
+
+    pub fn set_speed(&mut self, speed: ReplaySpeed) {
+        self.offset = self.elapsed();
+        self.resumed = Instant::now();
+        self.speed = speed;
+    }
+
+    pub fn restart(&mut self) {
+        self.offset = Duration::ZERO;
+        self.resumed = Instant::now();
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.offset + self.resumed.elapsed() * self.speed as u32
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed() >= self.log.duration()
+    }
+
+    pub fn render(&self, style: &crate::Style, area: Rect, buf: &mut Buffer) {
+        let [header, body] =
+            Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(area);
+
+        let elapsed = self.elapsed().min(self.log.duration());
+        let (typed_text, was_typed_wrong) = self.log.state_at(elapsed);
+
+        Line::from_iter([
+            Span::styled(
+                format!("replay {}x ", self.speed as u8),
+                Style::new().fg(style.theme.main).bold(),
+            ),
+            Span::styled(
+                format!(
+                    "{:.1}s / {:.1}s   [1/2/4] speed  [r] restart  [q] quit",
+                    elapsed.as_secs_f32(),
+                    self.log.duration().as_secs_f32()
+                ),
+                Style::new().fg(style.theme.sub),
+            ),
+        ])
+        .render(header, buf);
+
+        super::render_text(
+            self.log.text(),
+            &typed_text,
+            &was_typed_wrong,
+            &super::Funboxes::default(),
+            style,
+            body,
+            buf,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log() -> KeystrokeLog {
+        let mut log = KeystrokeLog::new("ab".to_string());
+        log.keystrokes = vec![
+            Keystroke { time: 0, index: 0, key: Some('a'), outcome: Outcome::Correct },
+            Keystroke { time: 100, index: 1, key: Some('x'), outcome: Outcome::Incorrect },
+            Keystroke { time: 200, index: 1, key: None, outcome: Outcome::Backspace },
+            Keystroke { time: 300, index: 1, key: Some('b'), outcome: Outcome::Correct },
+        ];
+        log
+    }
+
+    #[test]
+    fn state_at() {
+        let log = log();
+
+        let (typed, wrong) = log.state_at(Duration::from_millis(150));
+        assert_eq!(typed, vec!['a', 'x']);
+        assert!(wrong.contains(&1));
+
+        let (typed, _) = log.state_at(Duration::from_millis(250));
+        assert_eq!(typed, vec!['a']);
+
+        let (typed, wrong) = log.state_at(log.duration());
+        assert_eq!(typed, vec!['a', 'b']);
+        assert!(wrong.contains(&1));
+    }
+
+    #[test]
+    fn serialization_round_trip() {
+        let log = log();
+        let json = serde_json::to_string(&log).unwrap();
+        assert!(json.contains(r#"{"t":200,"i":1,"o":"b"}"#));
+
+        let parsed: KeystrokeLog = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.keystrokes, log.keystrokes);
+        assert_eq!(parsed.text, log.text);
+    }
+
+    #[test]
+    fn skip_invalid_files() {
+        let dir = std::env::temp_dir().join(format!("typ-replays-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let mut log = log();
+        log.timestamp = 2;
+        fs::write(dir.join("2.json"), serde_json::to_vec(&log).unwrap()).unwrap();
+        fs::write(dir.join("1.json"), "{").unwrap();
+        fs::write(dir.join("notes.txt"), "{").unwrap();
+
+        let mut errors = Vec::new();
+        let logs = KeystrokeLog::load_dir(&dir, &mut errors).unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].timestamp, 2);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].to_string().contains("1.json"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
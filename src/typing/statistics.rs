@@ -1,9 +1,11 @@
+use crate::user::stats::KeyStats;
+
 use ratatui::{
     prelude::*,
     widgets::{Axis, Block, Chart, Dataset, GraphType, LegendPosition},
 };
 
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
 #[derive(Debug)]
 pub struct Char {
@@ -34,6 +36,11 @@ impl Char {
     pub fn is_char(&self, char: char) -> bool {
         self.actual == Some(char) || (self.actual.is_none() && self.typed == char)
     }
+
+    /// The char that should have been typed.
+    pub fn expected(&self) -> char {
+        self.actual.unwrap_or(self.typed)
+    }
 }
 
 pub struct TestStatistics {
@@ -94,6 +101,20 @@ impl TestStatistics {
         }
     }
 
+    pub fn is_started(&self) -> bool {
+        self.started.is_some()
+    }
+
+    pub fn duration(&self) -> Duration {
+        match self.started {
+            Some(started) => self
+                .ended_or_now()
+                .duration_since(started)
+                .unwrap_or_default(),
+            None => Duration::ZERO,
+        }
+    }
+
     pub fn new_char(&mut self, index: usize, typed: char, actual: char) {
         let now = SystemTime::now();
         let diff = match now.duration_since(self.last_char_typed()) {
@@ -146,6 +167,59 @@ impl TestStatistics {
         (right_chars as f64 / minutes).round() as usize
     }
 
+    /// Like [`Self::cpm`], but counts every keystroke, including the wrong ones.
+    pub fn raw_cpm(&self) -> usize {
+        let minutes = self.duration().as_secs_f64() / 60.0;
+        if minutes == 0.0 {
+            return 0;
+        }
+
+        (self.chars.len() as f64 / minutes).round() as usize
+    }
+
+    pub fn raw_wpm(&self) -> usize {
+        self.raw_cpm() / crate::CHARS_PER_WORD as usize
+    }
+
+    /// Consistency of the time between keystrokes in percent, mapped the same way monkeytype
+    /// does it (`100 * (1 - tanh(cv + cv^3 / 3 + cv^5 / 5))`, where `cv` is the coefficient of
+    /// variation).
+    pub fn consistency(&self) -> f32 {
+        // The first char has no previous char to measure the time against.
+        let diffs: Vec<f64> = self.chars.iter().skip(1).map(|c| c.diff as f64).collect();
+        if diffs.len() < 2 {
+            return 0.0;
+        }
+
+        let mean = diffs.iter().sum::<f64>() / diffs.len() as f64;
+        if mean == 0.0 {
+            return 0.0;
+        }
+
+        let variance = diffs.iter().map(|d| (d - mean).powi(2)).sum::<f64>() / diffs.len() as f64;
+        let cv = variance.sqrt() / mean;
+
+        (100.0 * (1.0 - (cv + cv.powi(3) / 3.0 + cv.powi(5) / 5.0).tanh())) as f32
+    }
+
+    /// Latency and errors per key and bigram of this test.
+    pub fn key_stats(&self) -> KeyStats {
+        let mut stats = KeyStats::default();
+        let mut previous: Option<&Char> = None;
+
+        for char in &self.chars {
+            // The first char has no previous char to measure the time against.
+            let latency = previous.map(|_| char.diff.min(u32::MAX as u128) as u32);
+            let bigram = previous
+                .filter(|p| p.index + 1 == char.index)
+                .map(|p| p.expected());
+            stats.record(bigram, char.expected(), latency, char.is_error());
+            previous = Some(char);
+        }
+
+        stats
+    }
+
     fn wpm_and_error_indexes_for_each_char(chars: &[Char]) -> (Vec<usize>, Vec<usize>) {
         let word_count = 1 + chars.iter().filter(|c| c.is_char(' ')).count();
 
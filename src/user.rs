@@ -1,5 +1,5 @@
 pub mod config;
 pub mod stats;
 
-pub use config::Config;
+pub use config::{Config, ConfigOverrides};
 pub use stats::Stats;
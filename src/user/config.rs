@@ -1,26 +1,264 @@
 pub mod style;
+pub mod watch;
 
-pub use style::{Style, Theme};
+pub use style::{CaretStyle, Style, Theme};
+pub use watch::ConfigWatcher;
+
+use crate::monkeytype::{KeyboardLayout, Language};
+use crate::typing::{Difficulty, ErrorFeedback, Mode, QuoteLength};
+use crate::user::stats::ModeKind;
 
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Config {
     pub style: Style,
+    /// Mode of the next test. Custom texts only come from challenges, so `custom` is typed as
+    /// words.
+    #[serde(default = "default_mode")]
+    pub mode: ModeKind,
+    /// Seconds of a time test or words of a words test.
+    #[serde(default = "default_length")]
+    pub length: usize,
+    #[serde(default = "default_quote_lengths")]
+    pub quote_lengths: Vec<QuoteLength>,
+    #[serde(default)]
+    pub language: Language,
+    #[serde(default)]
+    pub punctuation: bool,
+    #[serde(default)]
+    pub numbers: bool,
+    #[serde(default)]
+    pub difficulty: Difficulty,
+    #[serde(
+        default,
+        deserialize_with = "KeyboardLayout::deserialize",
+        serialize_with = "KeyboardLayout::serialize"
+    )]
+    pub layout: KeyboardLayout,
+    /// Show the layout under the typing area and highlight the next key.
+    #[serde(default)]
+    pub keymap: bool,
+    /// Translate keys typed on a QWERTY keyboard into `layout`.
+    #[serde(default)]
+    pub emulate_layout: bool,
+    /// Names of the active funboxes, in the order they were enabled.
+    #[serde(default)]
+    pub funbox: Vec<String>,
+    /// Layouts for layoutfluid, in the order they are used. Empty means the default layouts.
+    #[serde(default)]
+    pub layoutfluid_layouts: Vec<String>,
+    /// Sound set in `data/sound` played on every key, like `click1`. `None` is silent.
+    #[serde(default)]
+    pub click_sound: Option<String>,
+    /// Sound set played on mistakes instead of the click, like `error1`.
+    #[serde(default)]
+    pub error_sound: Option<String>,
+    /// Seconds before the end of a time test a warning is played at.
+    #[serde(default)]
+    pub time_warning: Option<u64>,
+    /// Volume of all sounds, from 0 to 1.
+    #[serde(default = "default_volume")]
+    pub volume: f32,
+    /// Bell, flash or shake on every kind of mistake.
+    #[serde(default)]
+    pub error_feedback: ErrorFeedback,
+}
+
+fn default_mode() -> ModeKind {
+    ModeKind::Words
+}
+
+fn default_length() -> usize {
+    10
+}
+
+fn default_quote_lengths() -> Vec<QuoteLength> {
+    vec![QuoteLength::All]
+}
+
+fn default_volume() -> f32 {
+    0.5
 }
 
 impl Config {
+    pub fn path() -> PathBuf {
+        crate::dirs::config().join(crate::CONFIG_FILE)
+    }
+
     pub fn load() -> crate::Result<Self> {
-        let config_slice = fs::read(PathBuf::from(crate::CONFIG_DIR).join(crate::CONFIG_FILE)).ok();
+        Self::load_from(&Self::path())
+    }
+
+    /// The config at `path`, or the defaults if there is no config yet. Other errors, like
+    /// missing permissions, are returned, so that the config isn't saved over with the defaults.
+    fn load_from(path: &Path) -> crate::Result<Self> {
+        let content = match fs::read(path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => {
+                let message = format!("Failed to read {}: {err}", path.display());
+                return Err(crate::Error::Generic(message.into()));
+            }
+        };
+
+        serde_json::from_slice(&content).map_err(|err| crate::Error::parsing_file(path, err))
+    }
+
+    /// Loads the config like [`Self::load`], but never fails. When the config is invalid, the
+    /// settings that can be read are kept and the others are the defaults.
+    pub fn load_or_default() -> (Self, Option<crate::Error>) {
+        match Self::load() {
+            Ok(config) => (config, None),
+            Err(err) => {
+                let config = fs::read(Self::path())
+                    .ok()
+                    .and_then(|content| serde_json::from_slice(&content).ok())
+                    .map(|settings| Self::recover(&settings))
+                    .unwrap_or_default();
+                (config, Some(err))
+            }
+        }
+    }
+
+    /// The defaults with every setting of `settings` that is valid by itself.
+    fn recover(settings: &Value) -> Self {
+        fn merge(config: &mut Value, parent: &str, settings: &Value) {
+            let Some(settings) = settings.as_object() else {
+                return;
+            };
+            for (key, value) in settings {
+                let pointer = format!("{parent}/{key}");
+                if value.is_object() && config.pointer(&pointer).is_some_and(Value::is_object) {
+                    merge(config, &pointer, value);
+                    continue;
+                }
+
+                let mut candidate = config.clone();
+                if let Some(object) = candidate.pointer_mut(parent).and_then(Value::as_object_mut) {
+                    object.insert(key.clone(), value.clone());
+                }
+                if serde_json::from_value::<Config>(candidate.clone()).is_ok() {
+                    *config = candidate;
+                }
+            }
+        }
+
+        let Ok(mut config) = serde_json::to_value(Self::default()) else {
+            return Self::default();
+        };
+        merge(&mut config, "", settings);
+        serde_json::from_value(config).unwrap_or_default()
+    }
+
+    /// Writes the config, so that the next start uses the same settings.
+    pub fn save(&self) -> crate::Result<()> {
+        crate::user::stats::write_atomic(&Self::path(), &serde_json::to_vec_pretty(self)?)
+    }
+}
+
+impl Config {
+    /// The mode of the next test.
+    pub fn test_mode(&self) -> Mode {
+        let mode = match self.mode {
+            ModeKind::Time => Mode::time(self.length.into()),
+            ModeKind::Words | ModeKind::Custom => Mode::words(self.length.into()),
+            ModeKind::Quote => return Mode::quote(self.quote_lengths.clone()),
+        };
+        match (self.punctuation, self.numbers) {
+            (true, true) => mode.punctuation().numbers(),
+            (true, false) => mode.punctuation(),
+            (false, true) => mode.numbers(),
+            (false, false) => mode,
+        }
+    }
+
+    /// Whether `other` has different settings for tests, which are all settings but the style.
+    pub fn test_settings_differ(&self, other: &Config) -> bool {
+        let settings = |config: &Config| {
+            let mut value = serde_json::to_value(config).ok();
+            if let Some(object) = value.as_mut().and_then(|value| value.as_object_mut()) {
+                object.remove("style");
+            }
+            value
+        };
+        settings(self) != settings(other)
+    }
 
-        if let Some(slice) = config_slice {
-            serde_json::from_slice(&slice).map_err(|err| crate::Error::ParsingConfig(Box::new(err)))
+    /// The layouts layoutfluid uses.
+    pub fn layoutfluid_names(&self) -> Vec<&str> {
+        if self.layoutfluid_layouts.is_empty() {
+            crate::typing::funbox::layoutfluid::DEFAULT_LAYOUTS.to_vec()
         } else {
-            Ok(Self::default())
+            self.layoutfluid_layouts.iter().map(String::as_str).collect()
+        }
+    }
+}
+
+/// Settings given on the command line. They apply on top of the saved config, but aren't saved
+/// with it.
+#[derive(Clone, Debug, Default)]
+pub struct ConfigOverrides {
+    pub mode: Option<ModeKind>,
+    pub length: Option<usize>,
+    pub quote_lengths: Option<Vec<QuoteLength>>,
+    pub language: Option<Language>,
+    pub theme: Option<Theme>,
+    pub punctuation: Option<bool>,
+    pub numbers: Option<bool>,
+    /// Words of a custom text that is typed instead of the mode of the config.
+    pub text: Option<Vec<String>>,
+}
+
+impl ConfigOverrides {
+    /// Applies the overrides to `config` and returns the settings they replaced.
+    pub fn apply(&self, config: &mut Config) -> Self {
+        fn replace<T: Clone>(value: &Option<T>, setting: &mut T) -> Option<T> {
+            let value = value.clone()?;
+            Some(std::mem::replace(setting, value))
+        }
+
+        Self {
+            mode: replace(&self.mode, &mut config.mode),
+            length: replace(&self.length, &mut config.length),
+            quote_lengths: replace(&self.quote_lengths, &mut config.quote_lengths),
+            language: replace(&self.language, &mut config.language),
+            theme: replace(&self.theme, &mut config.style.theme),
+            punctuation: replace(&self.punctuation, &mut config.punctuation),
+            numbers: replace(&self.numbers, &mut config.numbers),
+            text: None,
+        }
+    }
+
+    /// `config` as it should be saved: every overridden setting that still has the value of the
+    /// override gets back the `replaced` setting.
+    pub fn unapply(&self, replaced: &Self, config: &Config) -> Config {
+        fn restore<T: Clone + PartialEq>(value: &Option<T>, replaced: &Option<T>, setting: &mut T) {
+            if let (Some(value), Some(replaced)) = (value, replaced)
+                && setting == value
+            {
+                *setting = replaced.clone();
+            }
+        }
+
+        let mut saved = config.clone();
+        restore(&self.mode, &replaced.mode, &mut saved.mode);
+        restore(&self.length, &replaced.length, &mut saved.length);
+        restore(&self.quote_lengths, &replaced.quote_lengths, &mut saved.quote_lengths);
+        restore(&self.language, &replaced.language, &mut saved.language);
+        restore(&self.punctuation, &replaced.punctuation, &mut saved.punctuation);
+        restore(&self.numbers, &replaced.numbers, &mut saved.numbers);
+        if let (Some(theme), Some(replaced)) = (&self.theme, &replaced.theme)
+            && saved.style.theme.name == theme.name
+        {
+            saved.style.theme = replaced.clone();
         }
+
+        saved
     }
 }
 
@@ -28,6 +266,106 @@ impl Default for Config {
     fn default() -> Self {
         Self {
             style: Style::default(),
+            mode: default_mode(),
+            length: default_length(),
+            quote_lengths: default_quote_lengths(),
+            language: Language::default(),
+            punctuation: false,
+            numbers: false,
+            difficulty: Difficulty::default(),
+            layout: KeyboardLayout::default(),
+            keymap: false,
+            emulate_layout: false,
+            funbox: Vec::new(),
+            layoutfluid_layouts: Vec::new(),
+            click_sound: None,
+            error_sound: None,
+            time_warning: None,
+            volume: default_volume(),
+            error_feedback: ErrorFeedback::default(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recover() {
+        let content = br#"{
+  "style": { "theme": "misspelled", "caret": "underline" },
+  "mode": "time",
+  "length": "thirty"
+}"#;
+        let Err(err) = serde_json::from_slice::<Config>(content) else {
+            panic!("invalid config was loaded");
+        };
+        let err = crate::Error::parsing_file("config.json", err).to_string();
+        assert!(err.starts_with("Failed to parse config.json:2:"), "{err}");
+        assert!(err.contains("themes/misspelled.json"), "{err}");
+
+        let config = Config::recover(&serde_json::from_slice(content).unwrap());
+        assert_eq!(config.style.theme.name, Theme::default().name);
+        assert_eq!(config.style.caret, CaretStyle::Underline);
+        assert_eq!(config.mode, ModeKind::Time);
+        assert_eq!(config.length, default_length());
+    }
+
+    #[test]
+    fn unreadable() {
+        let dir = std::env::temp_dir().join(format!("typ-config-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        assert!(Config::load_from(&dir.join("missing.json")).is_ok());
+        // A directory in place of the config can't be read.
+        let Err(err) = Config::load_from(&dir) else {
+            panic!("unreadable config was loaded");
+        };
+        assert!(err.to_string().starts_with("Failed to read"), "{err}");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn overrides() {
+        let mut config = Config::default();
+        let overrides = ConfigOverrides {
+            mode: Some(ModeKind::Time),
+            length: Some(60),
+            language: Some(Language::English1k),
+            ..Default::default()
+        };
+        let replaced = overrides.apply(&mut config);
+        assert_eq!((config.mode, config.length), (ModeKind::Time, 60));
+
+        config.length = 30;
+        let saved = overrides.unapply(&replaced, &config);
+        assert_eq!(saved.mode, ModeKind::Words);
+        assert_eq!(saved.length, 30);
+        assert_eq!(saved.language, Language::default());
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut config = Config::default();
+        config.style.border_type = ratatui::widgets::BorderType::QuadrantInside;
+        config.style.caret = CaretStyle::Underline;
+        config.mode = ModeKind::Time;
+        config.length = 30;
+        config.punctuation = true;
+
+        let json = serde_json::to_string(&config).unwrap();
+        let loaded: Config = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&loaded).unwrap(), json);
+        assert_eq!(loaded.style.border_type, config.style.border_type);
+        assert!(matches!(
+            loaded.test_mode(),
+            Mode::Time {
+                seconds: crate::typing::Seconds::S30,
+                punctuation: true,
+                numbers: false,
+            }
+        ));
+    }
+}
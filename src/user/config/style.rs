@@ -4,9 +4,10 @@ mod ratatui_wrappers;
 pub use theme::Theme;
 
 use serde::{Deserialize, Serialize};
+use ratatui::style::Stylize;
 use ratatui::widgets::BorderType;
 
-#[derive(Default, Deserialize, Serialize)]
+#[derive(Clone, Default, Deserialize, Serialize)]
 pub struct Style {
     #[serde(
         deserialize_with = "Theme::deserialize",
@@ -18,4 +19,43 @@ pub struct Style {
         serialize_with = "ratatui_wrappers::BorderType::serialize"
     )]
     pub border_type: BorderType,
+    #[serde(default)]
+    pub caret: CaretStyle,
+}
+
+/// How the next char to type is marked.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CaretStyle {
+    /// The background of the char in the caret color.
+    #[default]
+    Block,
+    /// The char underlined in the caret color.
+    Underline,
+    Off,
+}
+
+impl CaretStyle {
+    pub const ALL: [CaretStyle; 3] = [Self::Block, Self::Underline, Self::Off];
+
+    /// The style of the char under the caret.
+    pub fn apply(&self, style: ratatui::style::Style, theme: &Theme) -> ratatui::style::Style {
+        match self {
+            Self::Block => style.bg(theme.caret),
+            Self::Underline => style.underlined().underline_color(theme.caret),
+            Self::Off => style,
+        }
+    }
+}
+
+impl std::fmt::Display for CaretStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let string = match self {
+            Self::Block => "block",
+            Self::Underline => "underline",
+            Self::Off => "off",
+        };
+
+        write!(f, "{string}")
+    }
 }
@@ -25,7 +25,20 @@ impl BorderType {
     where
         S: ser::Serializer,
     {
-        serializer.serialize_str(border_type.to_string().as_str())
+        Serialize::serialize(&BorderType::from(*border_type), serializer)
+    }
+}
+
+impl From<RBorderType> for BorderType {
+    fn from(value: RBorderType) -> Self {
+        match value {
+            RBorderType::Plain => BorderType::Plain,
+            RBorderType::Rounded => BorderType::Rounded,
+            RBorderType::Double => BorderType::Double,
+            RBorderType::Thick => BorderType::Thick,
+            RBorderType::QuadrantInside => BorderType::QuadrantInside,
+            RBorderType::QuadrantOutside => BorderType::QuadrantOutside,
+        }
     }
 }
 
@@ -5,8 +5,11 @@ use crate::app::quick_menu::QuickMenuItem;
 use ratatui::style::Color;
 use serde::{Deserialize, Serialize, de, ser};
 
-use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Name of the theme that is built in instead of loaded from a file.
+const DEFAULT_NAME: &str = "default";
+const THEMES_DIR: &str = "themes";
 
 #[derive(Deserialize, Serialize)]
 struct ThemeSerializer {
@@ -85,18 +88,17 @@ pub struct Theme {
 }
 
 impl Theme {
-    fn get_dir_path() -> PathBuf {
-        PathBuf::from(crate::DATA_DIR).join("themes")
-    }
-
+    /// The theme called `name`, relative to the data directories.
     pub fn get_path(name: &str) -> PathBuf {
-        Theme::get_dir_path().join(format!("{name}.json"))
+        Path::new(THEMES_DIR).join(format!("{name}.json"))
     }
 
+    /// Loads the theme file called `name`. The built in default theme has no file.
     pub fn load(name: String) -> crate::Result<Self> {
-        let path = Self::get_path(&name);
-        let content = fs::read(path)?;
-        let theme_serializer: ThemeSerializer = serde_json::from_slice(&content)?;
+        if name == DEFAULT_NAME {
+            return Ok(Self::default());
+        }
+        let theme_serializer: ThemeSerializer = crate::dirs::read_json(Self::get_path(&name))?;
         let theme = theme_serializer.theme(name);
         Ok(theme)
     }
@@ -106,7 +108,7 @@ impl Theme {
         D: de::Deserializer<'de>,
     {
         let name: String = de::Deserialize::deserialize(deserializer)?;
-        Ok(Self::load(name).expect("could not deserialize theme"))
+        Self::load(name).map_err(de::Error::custom)
     }
 
     pub fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -116,34 +118,48 @@ impl Theme {
         serializer.serialize_str(&self.name)
     }
 
-    pub fn all() -> crate::Result<Vec<Theme>> {
+    /// Every theme that can be loaded, and the errors of the ones that can't.
+    pub fn all() -> crate::Result<(Vec<Theme>, Vec<crate::Error>)> {
         let mut themes = Vec::new();
+        let mut errors = Vec::new();
+        for name in Self::names()? {
+            match Theme::load(name) {
+                Ok(theme) => themes.push(theme),
+                Err(err) => errors.push(err),
+            }
+        }
+
+        Ok((themes, errors))
+    }
+
+    /// The user's own themes, which hide the bundled ones.
+    pub fn user_dir() -> PathBuf {
+        crate::dirs::user_data().join(THEMES_DIR)
+    }
 
-        for entry in fs::read_dir(Theme::get_dir_path())? {
-            let entry = entry?;
-            if let Some(name) = entry.path().file_name() {
-                if let Some(name) = name.to_str() {
-                    if let Some((file_name, extension)) = name.rsplit_once(".") {
-                        if extension == "json" {
-                            themes.push(Theme::load(file_name.to_string())?);
-                        }
-                    }
-                }
+    /// Names of all themes, sorted by name.
+    pub fn names() -> crate::Result<Vec<String>> {
+        let mut names = Vec::new();
+        for entry in crate::dirs::read_dir(THEMES_DIR)? {
+            if let Some(name) = entry.name.strip_suffix(".json") {
+                names.push(name.to_string());
             }
         }
 
-        Ok(themes)
+        Ok(names)
     }
 
-    pub fn all_quick_menu_items() -> crate::Result<QuickMenuItem> {
-        Ok(QuickMenuItem::from_iter(Self::all()?))
+    /// The themes that can be loaded as menu items, and the errors of the others.
+    pub fn all_quick_menu_items() -> crate::Result<(QuickMenuItem, Vec<crate::Error>)> {
+        let (themes, errors) = Self::all()?;
+        Ok((QuickMenuItem::from_iter(themes), errors))
     }
 }
 
 impl Default for Theme {
     fn default() -> Self {
         Self {
-            name: "default".to_string(),
+            name: DEFAULT_NAME.to_string(),
             bg: Color::Black,
             main: Color::Yellow,
             caret: Color::LightYellow,
@@ -0,0 +1,108 @@
+//! Notices when the config file or one of the user's themes changes while the app runs. The
+//! files are polled, since a few `stat` calls every half second cost nothing and need no
+//! platform specific file watching.
+
+use super::{Config, Theme};
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+const CHECK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Modification times of the watched files. A missing file has no time.
+type Stamp = Vec<(PathBuf, Option<SystemTime>)>;
+
+pub struct ConfigWatcher {
+    config: PathBuf,
+    themes: PathBuf,
+    stamp: Stamp,
+    last_check: Instant,
+}
+
+impl ConfigWatcher {
+    /// Watches the config file and the user's theme directory.
+    pub fn new() -> Self {
+        Self::watch(Config::path(), Theme::user_dir())
+    }
+
+    pub fn watch(config: PathBuf, themes: PathBuf) -> Self {
+        let stamp = stamp(&config, &themes);
+        Self {
+            config,
+            themes,
+            stamp,
+            last_check: Instant::now(),
+        }
+    }
+
+    /// Whether a watched file was changed, added or removed since the last call. Files are only
+    /// looked at every [`CHECK_INTERVAL`].
+    pub fn changed(&mut self) -> bool {
+        if self.last_check.elapsed() < CHECK_INTERVAL {
+            return false;
+        }
+        self.last_check = Instant::now();
+
+        let stamp = stamp(&self.config, &self.themes);
+        let changed = stamp != self.stamp;
+        self.stamp = stamp;
+        changed
+    }
+
+    /// Takes the current files as unchanged, e.g. after the app saved the config itself.
+    pub fn mark_seen(&mut self) {
+        self.stamp = stamp(&self.config, &self.themes);
+    }
+}
+
+impl Default for ConfigWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn stamp(config: &Path, themes: &Path) -> Stamp {
+    let modified = |path: &Path| fs::metadata(path).and_then(|meta| meta.modified()).ok();
+
+    let mut stamp = vec![(config.to_path_buf(), modified(config))];
+    if let Ok(entries) = fs::read_dir(themes) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let time = modified(&path);
+            stamp.push((path, time));
+        }
+    }
+    stamp.sort();
+
+    stamp
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changes() {
+        let dir = std::env::temp_dir().join(format!("typ-watch-{}", std::process::id()));
+        let themes = dir.join("themes");
+        fs::create_dir_all(&themes).unwrap();
+        let config = dir.join("config.json");
+
+        let mut watcher = ConfigWatcher::watch(config.clone(), themes.clone());
+        watcher.last_check -= CHECK_INTERVAL;
+        assert!(!watcher.changed());
+
+        fs::write(&config, "{}").unwrap();
+        assert!(!watcher.changed(), "checked again before the interval");
+        watcher.last_check -= CHECK_INTERVAL;
+        assert!(watcher.changed());
+
+        fs::write(themes.join("mine.json"), "{}").unwrap();
+        watcher.mark_seen();
+        watcher.last_check -= CHECK_INTERVAL;
+        assert!(!watcher.changed());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,12 +1,489 @@
-use serde::{Serialize, Deserialize};
+pub mod achievements;
+mod csv;
+pub mod export;
+pub mod filter;
+pub mod import;
+pub mod keys;
+pub mod personal_best;
+pub mod result;
+pub mod tags;
 
-#[derive(Serialize, Deserialize)]
-pub struct Stats {
+pub use achievements::{Achievement, Achievements, Progress};
+pub use export::ExportFormat;
+pub use filter::{DateRange, Filter};
+pub use import::ImportSummary;
+pub use keys::{KeyStat, KeyStats};
+pub use personal_best::{PbComparison, PbKey, PersonalBest, PersonalBests};
+pub use result::{CharCounts, LayoutWpm, ModeKind, ResultStatus, TestResult};
+pub use tags::{Tag, Tags};
+
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
+const HISTORY_FILE: &str = "history.jsonl";
+/// Lines of the history that couldn't be parsed, kept so they can be fixed by hand.
+const CORRUPT_HISTORY_FILE: &str = "history.corrupt.jsonl";
+const PERSONAL_BESTS_FILE: &str = "personal_bests.json";
+const TAGS_FILE: &str = "tags.json";
+const KEY_STATS_FILE: &str = "key_stats.json";
+const ACHIEVEMENTS_FILE: &str = "achievements.json";
+const EXPORTS_DIR: &str = "exports";
+
+/// The result history, stored as JSON Lines with one [`TestResult`] per line, the personal
+/// bests derived from it, the tags results can be marked with, the per-key statistics and the
+/// completed challenges.
+pub struct Stats {
+    dir: PathBuf,
+    history: Vec<TestResult>,
+    personal_bests: PersonalBests,
+    tags: Tags,
+    key_stats: KeyStats,
+    achievements: Achievements,
+    /// Problems found while loading, e.g. invalid lines in the history.
+    errors: Vec<crate::Error>,
 }
 
 impl Stats {
-    pub fn load() -> Self {
-        Self {}
+    pub fn dir() -> PathBuf {
+        crate::dirs::state().to_path_buf()
+    }
+
+    pub fn load() -> crate::Result<Self> {
+        Self::load_from(Self::dir())
+    }
+
+    /// No results, tags, key statistics or achievements. Used when the stored ones can't be
+    /// read, so that new results are still appended to the history in `dir`.
+    pub fn empty(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            history: Vec::new(),
+            personal_bests: PersonalBests::default(),
+            tags: Tags::default(),
+            key_stats: KeyStats::default(),
+            achievements: Achievements::default(),
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn load_from(dir: PathBuf) -> crate::Result<Self> {
+        let path = dir.join(HISTORY_FILE);
+        let mut errors = Vec::new();
+        let tags = load_or_set_aside(
+            &dir.join(TAGS_FILE),
+            |path| Tags::load(path.to_path_buf()),
+            &mut errors,
+        )?;
+        let key_stats = load_or_set_aside(&dir.join(KEY_STATS_FILE), KeyStats::load, &mut errors)?;
+        let achievements = load_or_set_aside(
+            &dir.join(ACHIEVEMENTS_FILE),
+            |path| Achievements::load(path.to_path_buf()),
+            &mut errors,
+        )?;
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err.into()),
+        };
+
+        // Appends write a whole line at once, so only the last line can be incomplete after a
+        // crash. It is dropped here and the file is repaired before anything new is appended.
+        let (complete, mut repair) = match content.rfind('\n') {
+            Some(end) => (&content[..=end], end + 1 != content.len()),
+            None => ("", !content.is_empty()),
+        };
+
+        // Invalid lines, e.g. from editing the file by hand, are moved to another file, so that
+        // the rest of the history can still be used.
+        let mut history = Vec::with_capacity(complete.len() / 256);
+        let mut corrupt = String::new();
+        let mut invalid_lines = Vec::new();
+        for (i, line) in complete.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(line) {
+                Ok(result) => history.push(result),
+                Err(err) => {
+                    invalid_lines.push(crate::Error::ParsingHistory {
+                        path: path.clone(),
+                        line: i + 1,
+                        err: Box::new(err),
+                    });
+                    corrupt.push_str(line);
+                    corrupt.push('\n');
+                }
+            }
+        }
+        if !corrupt.is_empty() {
+            let corrupt_path = dir.join(CORRUPT_HISTORY_FILE);
+            let mut file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&corrupt_path)?;
+            file.write_all(corrupt.as_bytes())?;
+            file.sync_data()?;
+            let first = invalid_lines.remove(0);
+            let moved = format!("moved to {}", corrupt_path.display());
+            let message = match invalid_lines.len() {
+                0 => format!("{first}, {moved}"),
+                more => format!("{first} and {more} more invalid lines, {moved}"),
+            };
+            errors.push(crate::Error::Generic(message.into()));
+            repair = true;
+        }
+
+        let personal_bests = PersonalBests::load(&dir.join(PERSONAL_BESTS_FILE), &history)?;
+
+        let stats = Self {
+            dir,
+            history,
+            personal_bests,
+            tags,
+            key_stats,
+            achievements,
+            errors,
+        };
+        if repair {
+            stats.write_history()?;
+        }
+
+        Ok(stats)
+    }
+
+    /// Returns the problems found while loading, once.
+    pub fn take_errors(&mut self) -> Vec<crate::Error> {
+        std::mem::take(&mut self.errors)
+    }
+
+    pub fn history_path(&self) -> PathBuf {
+        self.dir.join(HISTORY_FILE)
+    }
+
+    pub fn history(&self) -> &[TestResult] {
+        &self.history
+    }
+
+    pub fn completed(&self) -> impl Iterator<Item = &TestResult> {
+        self.history.iter().filter(|r| r.is_completed())
+    }
+
+    pub fn personal_bests(&self) -> &PersonalBests {
+        &self.personal_bests
+    }
+
+    pub fn tags(&self) -> &Tags {
+        &self.tags
+    }
+
+    pub fn tags_mut(&mut self) -> &mut Tags {
+        &mut self.tags
+    }
+
+    /// Appends `result` to the history file and updates the personal bests. Returns how the
+    /// result compares to the previous personal best, if it can be one.
+    pub fn add(&mut self, result: TestResult) -> crate::Result<Option<PbComparison>> {
+        fs::create_dir_all(&self.dir)?;
+
+        let mut line = serde_json::to_vec(&result)?;
+        line.push(b'\n');
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.history_path())?;
+        file.write_all(&line)?;
+        file.sync_data()?;
+
+        let comparison = self.personal_bests.update(&result);
+        self.history.push(result);
+        self.personal_bests
+            .save(&self.dir.join(PERSONAL_BESTS_FILE), self.history.len())?;
+
+        Ok(comparison)
+    }
+
+    pub fn key_stats(&self) -> &KeyStats {
+        &self.key_stats
+    }
+
+    /// Merges the key statistics of one or more tests into the stored ones.
+    pub fn add_key_stats(&mut self, key_stats: &KeyStats) -> crate::Result<()> {
+        self.key_stats.merge(key_stats);
+        self.key_stats.save(&self.dir.join(KEY_STATS_FILE))
+    }
+
+    pub fn achievements(&self) -> &Achievements {
+        &self.achievements
+    }
+
+    /// Records that `result` completed the challenge called `challenge`.
+    pub fn add_achievement(&mut self, challenge: &str, result: TestResult) -> crate::Result<()> {
+        self.achievements.add(challenge, result)
+    }
+
+    pub fn exports_dir(&self) -> PathBuf {
+        self.dir.join(EXPORTS_DIR)
+    }
+
+    /// Writes all results matching `filter` to `path`. Returns the number of exported results.
+    pub fn export(&self, filter: &Filter, path: &Path, format: ExportFormat) -> crate::Result<usize> {
+        let results = filter.apply(&self.history);
+        let content = match format {
+            ExportFormat::Csv => export::to_csv(&results, &self.personal_bests),
+            ExportFormat::Json => export::to_json(&results)?,
+        };
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, content)?;
+
+        Ok(results.len())
+    }
+
+    /// Imports a result export from monkeytype's account page. Results that are already in the
+    /// history are skipped, so importing the same file again does nothing.
+    pub fn import_monkeytype_csv(&mut self, path: &Path) -> crate::Result<ImportSummary> {
+        let content = fs::read_to_string(path)?;
+        let (results, skipped) = import::parse_monkeytype_csv(&content);
+
+        let mut summary = ImportSummary {
+            skipped,
+            ..Default::default()
+        };
+
+        // The timestamp has millisecond precision, which makes it unique enough to detect
+        // results that were already imported.
+        let mut known: HashSet<u64> = self.history.iter().map(|r| r.timestamp).collect();
+        for result in results {
+            if known.insert(result.timestamp) {
+                self.history.push(result);
+                summary.imported += 1;
+            } else {
+                summary.duplicates += 1;
+            }
+        }
+
+        if summary.imported != 0 {
+            self.history.sort_by_key(|r| r.timestamp);
+            self.write_history()?;
+            self.personal_bests = PersonalBests::rebuild(&self.history);
+            self.personal_bests
+                .save(&self.dir.join(PERSONAL_BESTS_FILE), self.history.len())?;
+        }
+
+        Ok(summary)
+    }
+
+    /// Rewrites the whole history file. The new content is written to a temporary file first
+    /// and then renamed over the old one, so the file is never left half written.
+    fn write_history(&self) -> crate::Result<()> {
+        let mut content = Vec::with_capacity(self.history.len() * 256);
+        for result in &self.history {
+            serde_json::to_writer(&mut content, result)?;
+            content.push(b'\n');
+        }
+
+        write_atomic(&self.history_path(), &content)
+    }
+}
+
+/// Loads the file at `path` with `load`. A file that can't be loaded is moved to
+/// `*.corrupt.json`, so that it isn't overwritten, and loaded again as if it didn't exist.
+fn load_or_set_aside<T>(
+    path: &Path,
+    load: impl Fn(&Path) -> crate::Result<T>,
+    errors: &mut Vec<crate::Error>,
+) -> crate::Result<T> {
+    match load(path) {
+        Ok(loaded) => Ok(loaded),
+        Err(err) => {
+            let aside = path.with_extension("corrupt.json");
+            fs::rename(path, &aside)?;
+            errors.push(crate::Error::Generic(
+                format!(
+                    "Failed to load {}: {err}, moved to {}",
+                    path.display(),
+                    aside.display()
+                )
+                .into(),
+            ));
+            load(path)
+        }
+    }
+}
+
+pub(crate) fn write_atomic(path: &Path, content: &[u8]) -> crate::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+
+    let mut file = fs::File::create(&tmp)?;
+    file.write_all(content)?;
+    file.sync_all()?;
+    fs::rename(tmp, path)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monkeytype::Language;
+    use crate::typing::Difficulty;
+
+    pub(super) fn result(timestamp: u64) -> TestResult {
+        TestResult {
+            timestamp,
+            mode: ModeKind::Words,
+            length: 10,
+            quote_id: None,
+            language: Language::English,
+            punctuation: false,
+            numbers: false,
+            difficulty: Difficulty::Normal,
+            wpm: 80.0,
+            raw_wpm: 85.0,
+            accuracy: 97.5,
+            consistency: 70.0,
+            chars: CharCounts::default(),
+            duration: 7.5,
+            status: ResultStatus::Completed,
+            tags: Vec::new(),
+            funbox: Vec::new(),
+            layouts: Vec::new(),
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("typ-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn append_and_repair() {
+        let dir = temp_dir("history");
+        let path = dir.join(HISTORY_FILE);
+
+        let mut stats = Stats::load_from(dir.clone()).unwrap();
+        stats.add(result(1)).unwrap();
+        stats.add(result(2)).unwrap();
+
+        // Simulate a crash in the middle of an append.
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(br#"{"timestamp":3,"mo"#).unwrap();
+
+        let mut stats = Stats::load_from(dir.clone()).unwrap();
+        assert_eq!(stats.history(), &[result(1), result(2)]);
+
+        stats.add(result(4)).unwrap();
+        let stats = Stats::load_from(dir.clone()).unwrap();
+        assert_eq!(stats.history(), &[result(1), result(2), result(4)]);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn corrupt_lines() {
+        let dir = temp_dir("corrupt");
+        let path = dir.join(HISTORY_FILE);
+
+        let mut stats = Stats::load_from(dir.clone()).unwrap();
+        stats.add(result(1)).unwrap();
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"timestamp\":2}\n").unwrap();
+        stats.add(result(3)).unwrap();
+
+        let mut stats = Stats::load_from(dir.clone()).unwrap();
+        assert_eq!(stats.history(), &[result(1), result(3)]);
+        let errors = stats.take_errors();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].to_string().contains(":2:"), "{}", errors[0]);
+        assert_eq!(
+            fs::read_to_string(dir.join(CORRUPT_HISTORY_FILE)).unwrap(),
+            "{\"timestamp\":2}\n"
+        );
+
+        // The line was set aside, so it isn't reported again.
+        let mut stats = Stats::load_from(dir.clone()).unwrap();
+        assert!(stats.take_errors().is_empty());
+        assert_eq!(stats.history(), &[result(1), result(3)]);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn corrupt_files() {
+        let dir = temp_dir("corrupt-files");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(TAGS_FILE), "[{").unwrap();
+
+        let mut stats = Stats::load_from(dir.clone()).unwrap();
+        assert_eq!(stats.tags().iter().count(), 0);
+        assert_eq!(stats.take_errors().len(), 1);
+        assert_eq!(fs::read_to_string(dir.join("tags.corrupt.json")).unwrap(), "[{");
+
+        stats.tags_mut().create("new").unwrap();
+        let stats = Stats::load_from(dir.clone()).unwrap();
+        assert_eq!(stats.tags().active(), ["new"]);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn personal_bests() {
+        let dir = temp_dir("pbs");
+        let mut stats = Stats::load_from(dir.clone()).unwrap();
+
+        let first = stats.add(result(1)).unwrap().unwrap();
+        assert!(first.is_new());
+        assert_eq!(first.delta(), None);
+
+        let slower = stats
+            .add(TestResult {
+                wpm: 70.0,
+                ..result(2)
+            })
+            .unwrap()
+            .unwrap();
+        assert!(!slower.is_new());
+        assert_eq!(slower.delta(), Some(-10.0));
+
+        let faster = stats
+            .add(TestResult {
+                wpm: 90.0,
+                ..result(3)
+            })
+            .unwrap()
+            .unwrap();
+        assert!(faster.is_new());
+        assert_eq!(faster.delta(), Some(10.0));
+
+        let funbox = TestResult {
+            wpm: 200.0,
+            funbox: vec!["mirror".to_string()],
+            ..result(4)
+        };
+        assert_eq!(stats.add(funbox).unwrap(), None);
+
+        // Losing the cache rebuilds it from the history.
+        fs::remove_file(dir.join(PERSONAL_BESTS_FILE)).unwrap();
+        let stats = Stats::load_from(dir.clone()).unwrap();
+        let pb = stats
+            .personal_bests()
+            .get(&PbKey::from(&result(0)))
+            .unwrap();
+        assert_eq!(pb.wpm, 90.0);
+        assert_eq!(pb.timestamp, 3);
+
+        fs::remove_dir_all(dir).unwrap();
     }
 }
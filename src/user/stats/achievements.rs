@@ -0,0 +1,163 @@
+//! Challenges that were completed, each with the first result that met its requirements.
+
+use super::TestResult;
+use crate::monkeytype::{Challenge, ChallengeKind};
+
+use serde::{Deserialize, Serialize};
+
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Achievement {
+    /// Name of the challenge in the challenge list.
+    pub challenge: String,
+    /// The result that completed the challenge. Its timestamp is the date of the achievement.
+    pub result: TestResult,
+}
+
+#[derive(Default)]
+pub struct Achievements {
+    path: PathBuf,
+    achievements: Vec<Achievement>,
+}
+
+impl Achievements {
+    pub fn load(path: PathBuf) -> crate::Result<Self> {
+        let achievements = match fs::read(&path) {
+            Ok(content) => serde_json::from_slice(&content)?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err.into()),
+        };
+
+        Ok(Self { path, achievements })
+    }
+
+    fn save(&self) -> crate::Result<()> {
+        super::write_atomic(&self.path, &serde_json::to_vec_pretty(&self.achievements)?)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Achievement> {
+        self.achievements.iter()
+    }
+
+    pub fn get(&self, challenge: &str) -> Option<&Achievement> {
+        self.achievements.iter().find(|a| a.challenge == challenge)
+    }
+
+    /// Records that `result` completed `challenge`. Completing a challenge again keeps the first
+    /// result.
+    pub fn add(&mut self, challenge: &str, result: TestResult) -> crate::Result<()> {
+        if self.get(challenge).is_some() {
+            return Ok(());
+        }
+
+        self.achievements.push(Achievement {
+            challenge: challenge.to_string(),
+            result,
+        });
+        self.save()
+    }
+}
+
+/// How far the history is from completing a challenge that wasn't completed yet.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Progress {
+    /// Seconds typed in all tests, for challenges about long tests.
+    Time { typed: f64, goal: f64 },
+    /// The best WPM of a completed test, for challenges with a minimum WPM.
+    Wpm { best: f64, goal: f64 },
+}
+
+impl Progress {
+    /// Progress of `history` towards `challenge`, if the challenge has a goal that can be
+    /// approached over several tests.
+    pub fn new(challenge: &Challenge, history: &[TestResult]) -> Option<Self> {
+        let requirements = &challenge.requirements;
+        let min_wpm = requirements.wpm.as_ref().and_then(|wpm| wpm.min);
+        let min_time = requirements.time.as_ref().and_then(|time| time.min);
+        let typed = || history.iter().map(|r| r.duration).sum();
+
+        // A required speed is harder to reach than a required length, unless the length is
+        // what the challenge is about.
+        match (&challenge.kind, min_wpm, min_time) {
+            (ChallengeKind::CustomTime { seconds }, _, _) => Some(Self::Time {
+                typed: typed(),
+                goal: *seconds as f64,
+            }),
+            (_, Some(goal), _) => {
+                let best = history
+                    .iter()
+                    .filter(|r| r.is_completed())
+                    .map(|r| r.wpm as f64)
+                    .fold(0.0, f64::max);
+                Some(Self::Wpm { best, goal })
+            }
+            (_, None, Some(goal)) => Some(Self::Time {
+                typed: typed(),
+                goal,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Between 0 and 1.
+    pub fn ratio(&self) -> f64 {
+        let (value, goal) = match *self {
+            Self::Time { typed, goal } => (typed, goal),
+            Self::Wpm { best, goal } => (best, goal),
+        };
+
+        if goal <= 0.0 {
+            1.0
+        } else {
+            (value / goal).min(1.0)
+        }
+    }
+}
+
+impl fmt::Display for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let minutes = |seconds: f64| {
+            let seconds = seconds as u64;
+            format!("{}:{:02}", seconds / 60, seconds % 60)
+        };
+
+        match *self {
+            Self::Time { typed, goal } => {
+                write!(f, "{} / {} typed", minutes(typed.min(goal)), minutes(goal))
+            }
+            Self::Wpm { best, goal } => write!(f, "{best:.0} / {goal:.0} wpm"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn progress() {
+        let hour = Challenge::load("oneHourWarrior").unwrap();
+        let mut result = crate::user::stats::tests::result(0);
+        result.duration = 900.0;
+        let progress = Progress::new(&hour, &[result.clone(), result.clone()]).unwrap();
+        assert_eq!(progress.ratio(), 0.5);
+        assert_eq!(progress.to_string(), "30:00 / 60:00 typed");
+
+        let jolly = Challenge::load("jolly").unwrap();
+        let progress = Progress::new(&jolly, &[result.clone()]).unwrap();
+        assert_eq!(
+            progress,
+            Progress::Wpm {
+                best: 80.0,
+                goal: 70.0
+            }
+        );
+        assert_eq!(progress.ratio(), 1.0);
+
+        let simp = Challenge::load("simp").unwrap();
+        assert_eq!(Progress::new(&simp, &[result]), None);
+    }
+}
@@ -0,0 +1,83 @@
+//! Minimal CSV support (RFC 4180) for importing and exporting the result history.
+
+/// Parses `content` into records. Fields may be quoted, quoted fields may contain commas,
+/// newlines and `""` as an escaped quote.
+pub fn parse(content: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            ('"', true) => in_quotes = false,
+            ('"', false) if field.is_empty() => in_quotes = true,
+            (',', false) => record.push(std::mem::take(&mut field)),
+            ('\r', false) if chars.peek() == Some(&'\n') => (),
+            ('\n', false) => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            (c, _) => field.push(c),
+        }
+    }
+
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+
+    records
+}
+
+/// Quotes `field` if it contains characters that would otherwise break the record.
+pub fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+pub fn write_record<S: AsRef<str>>(out: &mut String, fields: &[S]) {
+    for (i, field) in fields.iter().enumerate() {
+        if i != 0 {
+            out.push(',');
+        }
+        out.push_str(&escape(field.as_ref()));
+    }
+    out.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quoted() {
+        assert_eq!(
+            parse("plain,\"with,comma\",\"with \"\"quotes\"\"\",\"multi\nline\",\n"),
+            vec![vec!["plain", "with,comma", "with \"quotes\"", "multi\nline", ""]]
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let fields = ["plain", "with,comma", "with \"quotes\"", "multi\nline", ""];
+        let mut out = String::new();
+        write_record(&mut out, &fields);
+        write_record(&mut out, &["a", "b"]);
+
+        assert_eq!(parse(&out), vec![fields.to_vec(), vec!["a", "b"]]);
+    }
+
+    #[test]
+    fn crlf() {
+        assert_eq!(parse("a,b\r\nc,d"), vec![vec!["a", "b"], vec!["c", "d"]]);
+    }
+}
@@ -0,0 +1,223 @@
+//! Export of the result history to CSV and JSON.
+//!
+//! The CSV columns are the same as in monkeytype's result export, so files can be read by the
+//! same tools and imported again (see [`super::import`]):
+//!
+//! | column                  | content                                                      |
+//! |-------------------------|--------------------------------------------------------------|
+//! | `_id`                   | the timestamp, local results don't have another id           |
+//! | `isPb`                  | `true` if the result is the current personal best            |
+//! | `wpm`                   | words per minute                                             |
+//! | `acc`                   | accuracy in percent                                          |
+//! | `rawWpm`                | words per minute, counting wrong keystrokes as well          |
+//! | `consistency`           | consistency in percent                                       |
+//! | `charStats`             | `correct;incorrect;extra;missed` chars                       |
+//! | `mode`                  | `time`, `words`, `quote` or `custom`                         |
+//! | `mode2`                 | seconds, word count or the quote id                          |
+//! | `quoteLength`           | `0` short, `1` medium, `2` long, `3` thicc, `-1` not a quote |
+//! | `restartCount`          | always `0`                                                   |
+//! | `testDuration`          | duration in seconds                                          |
+//! | `afkDuration`           | always `0`                                                   |
+//! | `incompleteTestSeconds` | always `0`                                                   |
+//! | `lazyMode`              | always `false`                                               |
+//! | `blindMode`             | always `false`                                               |
+//! | `bailedOut`             | `true` if the test was aborted                               |
+//! | `tags`                  | tags separated by `;`                                        |
+//! | `funbox`                | funboxes separated by `;`                                    |
+//! | `language`              | language name, e.g. `english_1k`                             |
+//! | `punctuation`           | `true` or `false`                                            |
+//! | `numbers`               | `true` or `false`                                            |
+//! | `difficulty`            | `normal`, `expert` or `master`                               |
+//! | `timestamp`             | unix timestamp in milliseconds                               |
+
+use super::csv;
+use super::personal_best::{PbKey, PersonalBests};
+use super::result::{ModeKind, ResultStatus, TestResult};
+use crate::typing::QuoteLength;
+
+use std::path::Path;
+
+pub const CSV_COLUMNS: [&str; 24] = [
+    "_id",
+    "isPb",
+    "wpm",
+    "acc",
+    "rawWpm",
+    "consistency",
+    "charStats",
+    "mode",
+    "mode2",
+    "quoteLength",
+    "restartCount",
+    "testDuration",
+    "afkDuration",
+    "incompleteTestSeconds",
+    "lazyMode",
+    "blindMode",
+    "bailedOut",
+    "tags",
+    "funbox",
+    "language",
+    "punctuation",
+    "numbers",
+    "difficulty",
+    "timestamp",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    /// Uses JSON for `.json` files and CSV for everything else.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Self::Json,
+            _ => Self::Csv,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+        }
+    }
+}
+
+pub fn to_csv(results: &[&TestResult], personal_bests: &PersonalBests) -> String {
+    let mut out = String::new();
+    csv::write_record(&mut out, &CSV_COLUMNS);
+
+    for result in results {
+        let is_pb = personal_bests
+            .get(&PbKey::from(*result))
+            .is_some_and(|pb| pb.timestamp == result.timestamp);
+        let mode2 = match result.mode {
+            ModeKind::Quote => result.quote_id.unwrap_or_default(),
+            _ => result.length,
+        };
+        // Quotes of unknown length, like imported ones without a group, have the length of
+        // `QuoteLength::All` and are written without a group as well. `QuoteLength::from` never
+        // returns `All`, it counts the length as short.
+        let quote_length = match result.mode {
+            ModeKind::Quote if result.length == QuoteLength::All as usize => "-1",
+            ModeKind::Quote => match QuoteLength::from(result.length) {
+                QuoteLength::Short => "0",
+                QuoteLength::Medium => "1",
+                QuoteLength::Long => "2",
+                _ => "3",
+            },
+            _ => "-1",
+        };
+        let chars = &result.chars;
+
+        csv::write_record(
+            &mut out,
+            &[
+                result.timestamp.to_string(),
+                is_pb.to_string(),
+                format!("{:.2}", result.wpm),
+                format!("{:.2}", result.accuracy),
+                format!("{:.2}", result.raw_wpm),
+                format!("{:.2}", result.consistency),
+                format!(
+                    "{};{};{};{}",
+                    chars.correct, chars.incorrect, chars.extra, chars.missed
+                ),
+                result.mode.to_string(),
+                mode2.to_string(),
+                quote_length.to_string(),
+                "0".to_string(),
+                format!("{:.2}", result.duration),
+                "0".to_string(),
+                "0".to_string(),
+                "false".to_string(),
+                "false".to_string(),
+                (result.status == ResultStatus::Aborted).to_string(),
+                result.tags.join(";"),
+                result.funbox.join(";"),
+                result.language.to_string(),
+                result.punctuation.to_string(),
+                result.numbers.to_string(),
+                result.difficulty.to_string(),
+                result.timestamp.to_string(),
+            ],
+        );
+    }
+
+    out
+}
+
+pub fn to_json(results: &[&TestResult]) -> crate::Result<String> {
+    Ok(serde_json::to_string_pretty(results)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::import;
+    use super::super::tests::result;
+    use super::*;
+    use crate::monkeytype::Language;
+    use crate::typing::Difficulty;
+    use crate::user::stats::CharCounts;
+
+    #[test]
+    fn csv_round_trip() {
+        let result = TestResult {
+            mode: ModeKind::Quote,
+            length: QuoteLength::Medium as usize,
+            quote_id: Some(42),
+            language: Language::German,
+            difficulty: Difficulty::Master,
+            wpm: 88.5,
+            raw_wpm: 90.25,
+            consistency: 75.5,
+            chars: CharCounts {
+                correct: 200,
+                incorrect: 2,
+                extra: 1,
+                missed: 0,
+            },
+            tags: vec!["new keyboard".to_string(), "morning".to_string()],
+            ..result(1_705_000_000_000)
+        };
+
+        let personal_bests = PersonalBests::rebuild([&result]);
+        let csv = to_csv(&[&result], &personal_bests);
+        assert!(csv.starts_with("_id,isPb,wpm,acc,rawWpm,"));
+        assert!(csv.contains(",true,88.50,"));
+
+        let (imported, skipped) = import::parse_monkeytype_csv(&csv);
+        assert!(skipped.is_empty());
+        assert_eq!(imported, vec![result]);
+    }
+
+    #[test]
+    fn csv_round_trip_quote_lengths() {
+        let results: Vec<TestResult> = [
+            QuoteLength::All,
+            QuoteLength::Short,
+            QuoteLength::Medium,
+            QuoteLength::Long,
+            QuoteLength::Thicc,
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(i, length)| TestResult {
+            mode: ModeKind::Quote,
+            length: length as usize,
+            quote_id: Some(i),
+            ..result(1_705_000_000_000 + i as u64)
+        })
+        .collect();
+
+        let refs: Vec<&TestResult> = results.iter().collect();
+        let csv = to_csv(&refs, &PersonalBests::rebuild(refs.iter().copied()));
+        let (imported, skipped) = import::parse_monkeytype_csv(&csv);
+        assert!(skipped.is_empty());
+        assert_eq!(imported, results);
+    }
+}
@@ -0,0 +1,129 @@
+use super::result::{ModeKind, TestResult};
+use crate::monkeytype::Language;
+
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const DAY_MILLIS: u64 = 24 * 60 * 60 * 1000;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DateRange {
+    #[default]
+    All,
+    Today,
+    Week,
+    Month,
+    Year,
+}
+
+impl DateRange {
+    pub fn next(self) -> Self {
+        match self {
+            Self::All => Self::Today,
+            Self::Today => Self::Week,
+            Self::Week => Self::Month,
+            Self::Month => Self::Year,
+            Self::Year => Self::All,
+        }
+    }
+
+    /// The earliest unix timestamp in milliseconds that is part of the range.
+    pub fn since(&self, now: u64) -> u64 {
+        let days = match self {
+            Self::All => return 0,
+            Self::Today => return now - now % DAY_MILLIS,
+            Self::Week => 7,
+            Self::Month => 30,
+            Self::Year => 365,
+        };
+        now.saturating_sub(days * DAY_MILLIS)
+    }
+}
+
+impl fmt::Display for DateRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let string = match self {
+            Self::All => "all time",
+            Self::Today => "today",
+            Self::Week => "last 7 days",
+            Self::Month => "last 30 days",
+            Self::Year => "last 365 days",
+        };
+
+        write!(f, "{string}")
+    }
+}
+
+/// Selects a subset of the result history. `None` matches everything.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Filter {
+    pub mode: Option<ModeKind>,
+    pub language: Option<Language>,
+    pub date_range: DateRange,
+    pub tag: Option<String>,
+}
+
+impl Filter {
+    pub fn matches(&self, result: &TestResult) -> bool {
+        self.matches_at(result, now_millis())
+    }
+
+    fn matches_at(&self, result: &TestResult, now: u64) -> bool {
+        self.mode.is_none_or(|mode| mode == result.mode)
+            && self.language.is_none_or(|lang| lang == result.language)
+            && result.timestamp >= self.date_range.since(now)
+            && self.tag.as_ref().is_none_or(|tag| result.tags.contains(tag))
+    }
+
+    pub fn apply<'a>(&self, history: &'a [TestResult]) -> Vec<&'a TestResult> {
+        let now = now_millis();
+        history.iter().filter(|r| self.matches_at(r, now)).collect()
+    }
+}
+
+pub fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Formats a unix timestamp in milliseconds as `YYYY-MM-DD HH:MM` (UTC).
+pub fn format_timestamp(timestamp: u64) -> String {
+    let secs = timestamp / 1000;
+    let days = (secs / 86_400) as i64;
+    let (hours, minutes) = (secs % 86_400 / 3600, secs % 3600 / 60);
+
+    // Howard Hinnant's `civil_from_days`.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!("{year:04}-{month:02}-{day:02} {hours:02}:{minutes:02}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00");
+        assert_eq!(format_timestamp(1_709_210_096_000), "2024-02-29 12:34");
+    }
+
+    #[test]
+    fn date_range() {
+        let now = 10 * DAY_MILLIS + 1234;
+        assert_eq!(DateRange::All.since(now), 0);
+        assert_eq!(DateRange::Today.since(now), 10 * DAY_MILLIS);
+        assert_eq!(DateRange::Week.since(now), 3 * DAY_MILLIS + 1234);
+        assert_eq!(DateRange::Year.since(now), 0);
+    }
+}
@@ -0,0 +1,219 @@
+//! Import of the CSV file monkeytype's account page exports.
+
+use super::csv;
+use super::result::{CharCounts, ModeKind, ResultStatus, TestResult};
+use crate::monkeytype::Language;
+use crate::typing::{Difficulty, QuoteLength};
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+#[derive(Debug, Default)]
+pub struct ImportSummary {
+    pub imported: usize,
+    pub duplicates: usize,
+    /// Rows that could not be imported, with their row number and the reason.
+    pub skipped: Vec<(usize, String)>,
+}
+
+/// Parses a monkeytype result export. Columns are looked up by their header, so the order does
+/// not matter and unknown columns are ignored. Returns the parsed results and the rows that
+/// could not be parsed.
+pub fn parse_monkeytype_csv(content: &str) -> (Vec<TestResult>, Vec<(usize, String)>) {
+    let mut records = csv::parse(content).into_iter();
+    let Some(header) = records.next() else {
+        return (Vec::new(), Vec::new());
+    };
+    let columns: HashMap<&str, usize> = header
+        .iter()
+        .enumerate()
+        .map(|(i, name)| (name.trim(), i))
+        .collect();
+
+    let mut results = Vec::new();
+    let mut skipped = Vec::new();
+
+    for (i, record) in records.enumerate() {
+        if record.iter().all(|field| field.is_empty()) {
+            continue;
+        }
+        let row = Row {
+            columns: &columns,
+            record: &record,
+        };
+        match row.result() {
+            Ok(result) => results.push(result),
+            // The header is row 1.
+            Err(reason) => skipped.push((i + 2, reason)),
+        }
+    }
+
+    (results, skipped)
+}
+
+struct Row<'a> {
+    columns: &'a HashMap<&'a str, usize>,
+    record: &'a [String],
+}
+
+impl Row<'_> {
+    fn get(&self, column: &str) -> Option<&str> {
+        self.columns
+            .get(column)
+            .and_then(|i| self.record.get(*i))
+            .map(|field| field.trim())
+            .filter(|field| !field.is_empty())
+    }
+
+    fn parse<T: FromStr>(&self, column: &str) -> Result<T, String> {
+        let field = self.get(column).ok_or_else(|| format!("missing {column}"))?;
+        field
+            .parse()
+            .map_err(|_| format!("invalid {column}: {field}"))
+    }
+
+    fn flag(&self, column: &str) -> bool {
+        self.get(column) == Some("true")
+    }
+
+    fn result(&self) -> Result<TestResult, String> {
+        let mode2 = self.get("mode2").unwrap_or_default();
+        let (mode, length, quote_id) = match self.get("mode") {
+            Some("time") => (ModeKind::Time, self.parse("mode2")?, None),
+            Some("words") => (ModeKind::Words, self.parse("mode2")?, None),
+            Some("quote") => {
+                // monkeytype stores the quote group (0 = short, ..., 3 = thicc).
+                let length = match self.get("quoteLength") {
+                    Some("0") => QuoteLength::Short,
+                    Some("1") => QuoteLength::Medium,
+                    Some("2") => QuoteLength::Long,
+                    Some("3") => QuoteLength::Thicc,
+                    _ => QuoteLength::All,
+                };
+                (ModeKind::Quote, length as usize, mode2.parse().ok())
+            }
+            Some("custom") | Some("zen") => (ModeKind::Custom, mode2.parse().unwrap_or(0), None),
+            Some(mode) => return Err(format!("unknown mode: {mode}")),
+            None => return Err("missing mode".to_string()),
+        };
+
+        let language = match self.get("language") {
+            Some(name) => Language::from_str(name).map_err(|err| err.to_string())?,
+            None => Language::English,
+        };
+
+        let difficulty = match self.get("difficulty") {
+            None | Some("normal") => Difficulty::Normal,
+            Some("expert") => Difficulty::Expert,
+            Some("master") => Difficulty::Master,
+            Some(difficulty) => return Err(format!("unknown difficulty: {difficulty}")),
+        };
+
+        let chars = match self.get("charStats") {
+            Some(stats) => {
+                let counts = stats
+                    .split(';')
+                    .map(|count| count.trim().parse::<usize>())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|_| format!("invalid charStats: {stats}"))?;
+                let count = |i: usize| counts.get(i).copied().unwrap_or_default();
+                CharCounts {
+                    correct: count(0),
+                    incorrect: count(1),
+                    extra: count(2),
+                    missed: count(3),
+                }
+            }
+            None => CharCounts::default(),
+        };
+
+        let tags = self
+            .get("tags")
+            .map(|tags| {
+                tags.split(';')
+                    .map(str::trim)
+                    .filter(|tag| !tag.is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // monkeytype writes `none` if no funbox was active.
+        let funbox = self
+            .get("funbox")
+            .map(|funbox| {
+                funbox
+                    .split(';')
+                    .map(str::trim)
+                    .filter(|name| !name.is_empty() && *name != "none")
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let status = if self.flag("bailedOut") {
+            ResultStatus::Aborted
+        } else {
+            ResultStatus::Completed
+        };
+
+        Ok(TestResult {
+            timestamp: self.parse("timestamp")?,
+            mode,
+            length,
+            quote_id,
+            language,
+            punctuation: self.flag("punctuation"),
+            numbers: self.flag("numbers"),
+            difficulty,
+            wpm: self.parse("wpm")?,
+            raw_wpm: self.parse("rawWpm")?,
+            accuracy: self.parse("acc")?,
+            consistency: self.parse("consistency").unwrap_or_default(),
+            chars,
+            duration: self.parse("testDuration").unwrap_or_default(),
+            status,
+            tags,
+            funbox,
+            layouts: Vec::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CSV: &str = "\
+_id,isPb,wpm,acc,rawWpm,consistency,charStats,mode,mode2,quoteLength,restartCount,testDuration,afkDuration,incompleteTestSeconds,lazyMode,blindMode,bailedOut,tags,funbox,language,punctuation,numbers,difficulty,timestamp
+65a1,true,98.4,96.5,101.2,78.3,\"245;3;1;0\",time,30,-1,2,30,0,0,false,false,false,,none,english_1k,true,false,normal,1705000000000
+65a2,false,120,100,120,90,250;0;0;0,quote,142,0,0,20.5,0,0,false,false,false,abc;def,nospace;mirror,english,false,false,expert,1705000001000
+65a3,false,50,90,55,60,100;5;0;0,words,25,-1,0,30,0,0,false,false,false,,,not_a_language,false,false,normal,1705000002000
+";
+
+    #[test]
+    fn parse() {
+        let (results, skipped) = parse_monkeytype_csv(CSV);
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(skipped, vec![(4, "Unknown language: not_a_language".to_string())]);
+
+        let time = &results[0];
+        assert_eq!(time.mode, ModeKind::Time);
+        assert_eq!(time.length, 30);
+        assert_eq!(time.language, Language::English1k);
+        assert!(time.punctuation);
+        assert_eq!(time.chars.correct, 245);
+        assert_eq!(time.chars.extra, 1);
+        assert_eq!(time.timestamp, 1_705_000_000_000);
+        assert!(time.funbox.is_empty());
+
+        let quote = &results[1];
+        assert_eq!(quote.mode, ModeKind::Quote);
+        assert_eq!(quote.length, QuoteLength::Short as usize);
+        assert_eq!(quote.quote_id, Some(142));
+        assert_eq!(quote.difficulty, Difficulty::Expert);
+        assert_eq!(quote.tags, vec!["abc".to_string(), "def".to_string()]);
+        assert_eq!(quote.funbox, ["nospace", "mirror"]);
+    }
+}
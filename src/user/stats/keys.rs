@@ -0,0 +1,270 @@
+//! Latency and error rates per key and per bigram, aggregated over all tests.
+
+use serde::{Deserialize, Serialize};
+
+use ratatui::{prelude::*, widgets::Paragraph};
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Width of a latency histogram bucket in milliseconds.
+const BUCKET_MS: u32 = 10;
+/// Keystrokes that took longer than this are counted, but their latency is ignored, so that
+/// pauses don't make a key look slow.
+const MAX_LATENCY_MS: u32 = 2000;
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct KeyStat {
+    /// Number of keystrokes.
+    pub count: u32,
+    /// Number of wrong keystrokes.
+    pub errors: u32,
+    /// Sum of the timed latencies in milliseconds.
+    latency_sum: u64,
+    /// Number of timed keystrokes per latency bucket, which is enough to get the median
+    /// without keeping every sample.
+    histogram: BTreeMap<u32, u32>,
+}
+
+impl KeyStat {
+    fn record(&mut self, latency: Option<u32>, error: bool) {
+        self.count += 1;
+        if error {
+            self.errors += 1;
+        }
+        if let Some(latency) = latency.filter(|l| *l <= MAX_LATENCY_MS) {
+            self.latency_sum += latency as u64;
+            *self.histogram.entry(latency / BUCKET_MS).or_default() += 1;
+        }
+    }
+
+    pub fn merge(&mut self, other: &Self) {
+        self.count += other.count;
+        self.errors += other.errors;
+        self.latency_sum += other.latency_sum;
+        for (bucket, count) in &other.histogram {
+            *self.histogram.entry(*bucket).or_default() += count;
+        }
+    }
+
+    fn timed(&self) -> u32 {
+        self.histogram.values().sum()
+    }
+
+    /// Share of wrong keystrokes in percent.
+    pub fn error_rate(&self) -> f32 {
+        if self.count == 0 {
+            return 0.0;
+        }
+        self.errors as f32 / self.count as f32 * 100.0
+    }
+
+    /// Mean latency in milliseconds.
+    pub fn mean_latency(&self) -> Option<f32> {
+        let timed = self.timed();
+        (timed != 0).then(|| self.latency_sum as f32 / timed as f32)
+    }
+
+    /// Median latency in milliseconds, accurate to the middle of its histogram bucket.
+    pub fn median_latency(&self) -> Option<f32> {
+        let half = self.timed().div_ceil(2);
+        let mut seen = 0;
+        self.histogram.iter().find_map(|(bucket, count)| {
+            seen += count;
+            (seen >= half && half != 0)
+                .then(|| (*bucket * BUCKET_MS) as f32 + BUCKET_MS as f32 / 2.0)
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Ranking {
+    /// Highest median latency first.
+    Slowest,
+    /// Highest error rate first.
+    MostErrors,
+}
+
+/// [`KeyStat`]s for every key and every pair of consecutive keys, indexed by the characters
+/// that were supposed to be typed.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct KeyStats {
+    keys: BTreeMap<String, KeyStat>,
+    bigrams: BTreeMap<String, KeyStat>,
+}
+
+impl KeyStats {
+    pub fn load(path: &Path) -> crate::Result<Self> {
+        match fs::read(path) {
+            Ok(content) => Ok(serde_json::from_slice(&content)?),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> crate::Result<()> {
+        super::write_atomic(path, &serde_json::to_vec(self)?)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Records a keystroke for `key`. `previous` is the key before it, if it was typed directly
+    /// before, and `latency` the time since the previous keystroke.
+    pub fn record(&mut self, previous: Option<char>, key: char, latency: Option<u32>, error: bool) {
+        self.keys
+            .entry(key.to_string())
+            .or_default()
+            .record(latency, error);
+        if let Some(previous) = previous {
+            self.bigrams
+                .entry(format!("{previous}{key}"))
+                .or_default()
+                .record(latency, error);
+        }
+    }
+
+    pub fn merge(&mut self, other: &Self) {
+        for (key, stat) in &other.keys {
+            self.keys.entry(key.clone()).or_default().merge(stat);
+        }
+        for (bigram, stat) in &other.bigrams {
+            self.bigrams.entry(bigram.clone()).or_default().merge(stat);
+        }
+    }
+
+    pub fn key(&self, key: char) -> Option<&KeyStat> {
+        self.keys.get(&key.to_string())
+    }
+
+    pub fn bigram(&self, bigram: &str) -> Option<&KeyStat> {
+        self.bigrams.get(bigram)
+    }
+
+    /// The `n` keys ranked worst by `ranking`, ignoring keys with fewer than `min_samples`
+    /// keystrokes.
+    pub fn top_keys(&self, ranking: Ranking, min_samples: u32, n: usize) -> Vec<(&str, &KeyStat)> {
+        top(&self.keys, ranking, min_samples, n)
+    }
+
+    /// Like [`Self::top_keys`], but for bigrams.
+    pub fn top_bigrams(
+        &self,
+        ranking: Ranking,
+        min_samples: u32,
+        n: usize,
+    ) -> Vec<(&str, &KeyStat)> {
+        top(&self.bigrams, ranking, min_samples, n)
+    }
+
+    /// Renders the slowest and most error-prone keys and bigrams in four columns.
+    pub fn render(&self, min_samples: u32, style: &crate::Style, area: Rect, buf: &mut Buffer) {
+        let n = area.height.saturating_sub(1) as usize;
+        let columns = [
+            ("slowest keys", Ranking::Slowest, &self.keys),
+            ("slowest bigrams", Ranking::Slowest, &self.bigrams),
+            ("most missed keys", Ranking::MostErrors, &self.keys),
+            ("most missed bigrams", Ranking::MostErrors, &self.bigrams),
+        ];
+        let areas = Layout::horizontal([Constraint::Fill(1); 4]).split(area);
+
+        for ((title, ranking, stats), area) in columns.into_iter().zip(areas.iter()) {
+            let mut lines = vec![Line::styled(title, Style::new().fg(style.theme.main))];
+            for (key, stat) in top(stats, ranking, min_samples, n) {
+                let value = match ranking {
+                    Ranking::Slowest => {
+                        format!("{:.0}ms", stat.median_latency().unwrap_or_default())
+                    }
+                    Ranking::MostErrors => format!("{:.1}%", stat.error_rate()),
+                };
+                lines.push(Line::from_iter([
+                    Span::styled(
+                        format!("{:<4}", key.replace(' ', "␣")),
+                        Style::new().fg(style.theme.text),
+                    ),
+                    Span::styled(value, Style::new().fg(style.theme.sub)),
+                ]));
+            }
+            Paragraph::new(lines).render(*area, buf);
+        }
+    }
+}
+
+fn top(
+    stats: &BTreeMap<String, KeyStat>,
+    ranking: Ranking,
+    min_samples: u32,
+    n: usize,
+) -> Vec<(&str, &KeyStat)> {
+    let mut entries: Vec<(&str, &KeyStat, f32)> = stats
+        .iter()
+        .filter(|(_, stat)| stat.count >= min_samples)
+        .filter_map(|(key, stat)| {
+            let value = match ranking {
+                Ranking::Slowest => stat.median_latency()?,
+                Ranking::MostErrors if stat.errors == 0 => return None,
+                Ranking::MostErrors => stat.error_rate(),
+            };
+            Some((key.as_str(), stat, value))
+        })
+        .collect();
+    entries.sort_by(|a, b| b.2.total_cmp(&a.2));
+
+    entries
+        .into_iter()
+        .take(n)
+        .map(|(key, stat, _)| (key, stat))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aggregate() {
+        let mut stats = KeyStats::default();
+        stats.record(None, 't', None, false);
+        stats.record(Some('t'), 'h', Some(100), false);
+        stats.record(Some('h'), 'e', Some(300), true);
+
+        let mut other = KeyStats::default();
+        other.record(None, 'h', Some(50), false);
+        other.record(Some('h'), 'e', Some(120), false);
+        other.record(Some('e'), 'h', Some(5000), false);
+        stats.merge(&other);
+
+        let h = stats.key('h').unwrap();
+        assert_eq!(h.count, 3);
+        // The pause of 5 seconds is not timed.
+        assert_eq!(h.mean_latency(), Some(75.0));
+        assert_eq!(h.median_latency(), Some(55.0));
+        assert_eq!(stats.bigram("he").unwrap().error_rate(), 50.0);
+
+        let slowest = stats.top_bigrams(Ranking::Slowest, 1, 2);
+        assert_eq!(slowest[0].0, "he");
+        assert_eq!(slowest[1].0, "th");
+
+        let missed = stats.top_keys(Ranking::MostErrors, 1, 5);
+        assert_eq!(missed.len(), 1);
+        assert_eq!(missed[0].0, "e");
+    }
+
+    #[test]
+    fn slowest_by_median() {
+        let mut stats = KeyStats::default();
+        // A single slow keystroke makes the mean of `a` higher than the mean of `b`.
+        for latency in [10, 10, 900] {
+            stats.record(None, 'a', Some(latency), false);
+        }
+        for latency in [100, 100, 100] {
+            stats.record(None, 'b', Some(latency), false);
+        }
+
+        let slowest = stats.top_keys(Ranking::Slowest, 1, 2);
+        assert_eq!(slowest[0].0, "b");
+        assert_eq!(slowest[1].0, "a");
+    }
+}
@@ -0,0 +1,153 @@
+use super::result::{ModeKind, TestResult};
+use crate::monkeytype::Language;
+use crate::typing::Difficulty;
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+pub struct PbKey {
+    pub mode: ModeKind,
+    pub length: usize,
+    pub language: Language,
+    pub punctuation: bool,
+    pub numbers: bool,
+    pub difficulty: Difficulty,
+}
+
+impl From<&TestResult> for PbKey {
+    fn from(result: &TestResult) -> Self {
+        Self {
+            mode: result.mode,
+            length: result.length,
+            language: result.language,
+            punctuation: result.punctuation,
+            numbers: result.numbers,
+            difficulty: result.difficulty,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct PersonalBest {
+    #[serde(flatten)]
+    pub key: PbKey,
+    pub wpm: f32,
+    pub raw_wpm: f32,
+    pub accuracy: f32,
+    pub consistency: f32,
+    pub timestamp: u64,
+}
+
+impl From<&TestResult> for PersonalBest {
+    fn from(result: &TestResult) -> Self {
+        Self {
+            key: PbKey::from(result),
+            wpm: result.wpm,
+            raw_wpm: result.raw_wpm,
+            accuracy: result.accuracy,
+            consistency: result.consistency,
+            timestamp: result.timestamp,
+        }
+    }
+}
+
+/// How a result compares to the personal best that existed before it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PbComparison {
+    pub wpm: f32,
+    pub previous_best: Option<f32>,
+}
+
+impl PbComparison {
+    pub fn is_new(&self) -> bool {
+        self.previous_best.is_none_or(|best| self.wpm > best)
+    }
+
+    pub fn delta(&self) -> Option<f32> {
+        self.previous_best.map(|best| self.wpm - best)
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+struct PbCache {
+    /// Number of history entries the cache was built from, used to notice a stale cache.
+    history_len: usize,
+    bests: Vec<PersonalBest>,
+}
+
+/// Personal bests for every [`PbKey`]. They are cached on disk, but can always be rebuilt from
+/// the result history.
+#[derive(Default)]
+pub struct PersonalBests {
+    bests: HashMap<PbKey, PersonalBest>,
+}
+
+impl PersonalBests {
+    pub fn load(path: &Path, history: &[TestResult]) -> crate::Result<Self> {
+        let cache = fs::read(path)
+            .ok()
+            .and_then(|content| serde_json::from_slice::<PbCache>(&content).ok())
+            .filter(|cache| cache.history_len == history.len());
+
+        match cache {
+            Some(cache) => Ok(Self {
+                bests: cache.bests.into_iter().map(|pb| (pb.key, pb)).collect(),
+            }),
+            None => {
+                let pbs = Self::rebuild(history);
+                pbs.save(path, history.len())?;
+                Ok(pbs)
+            }
+        }
+    }
+
+    /// Builds the personal bests from `results`, e.g. the whole history or a filtered part.
+    pub fn rebuild<'a>(results: impl IntoIterator<Item = &'a TestResult>) -> Self {
+        let mut pbs = Self::default();
+        for result in results {
+            pbs.update(result);
+        }
+        pbs
+    }
+
+    pub fn save(&self, path: &Path, history_len: usize) -> crate::Result<()> {
+        let cache = PbCache {
+            history_len,
+            bests: self.bests.values().cloned().collect(),
+        };
+        super::write_atomic(path, &serde_json::to_vec(&cache)?)
+    }
+
+    pub fn get(&self, key: &PbKey) -> Option<&PersonalBest> {
+        self.bests.get(key)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &PersonalBest> {
+        self.bests.values()
+    }
+
+    /// Compares `result` with the current personal best and replaces it if `result` is better.
+    /// Returns `None` for results that can't be personal bests: incomplete tests and tests with
+    /// a funbox, which changes what is typed.
+    pub fn update(&mut self, result: &TestResult) -> Option<PbComparison> {
+        if !result.is_completed() || !result.funbox.is_empty() {
+            return None;
+        }
+
+        let key = PbKey::from(result);
+        let comparison = PbComparison {
+            wpm: result.wpm,
+            previous_best: self.bests.get(&key).map(|pb| pb.wpm),
+        };
+
+        if comparison.is_new() {
+            self.bests.insert(key, PersonalBest::from(result));
+        }
+
+        Some(comparison)
+    }
+}
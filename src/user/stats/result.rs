@@ -0,0 +1,117 @@
+use crate::monkeytype::Language;
+use crate::typing::{Difficulty, Mode};
+
+use serde::{Deserialize, Serialize};
+
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum ModeKind {
+    Time,
+    Words,
+    Quote,
+    Custom,
+}
+
+impl From<&Mode> for ModeKind {
+    fn from(mode: &Mode) -> Self {
+        match mode {
+            Mode::Time { .. } => Self::Time,
+            Mode::Words { .. } => Self::Words,
+            Mode::Quote { .. } => Self::Quote,
+            Mode::Custom { .. } => Self::Custom,
+        }
+    }
+}
+
+impl fmt::Display for ModeKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let string = match self {
+            Self::Time => "time",
+            Self::Words => "words",
+            Self::Quote => "quote",
+            Self::Custom => "custom",
+        };
+
+        write!(f, "{string}")
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ResultStatus {
+    Completed,
+    /// The test was failed because of the difficulty setting.
+    Failed,
+    /// The test was restarted before it was completed.
+    Aborted,
+}
+
+impl fmt::Display for ResultStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let string = match self {
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+            Self::Aborted => "aborted",
+        };
+
+        write!(f, "{string}")
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct CharCounts {
+    pub correct: usize,
+    pub incorrect: usize,
+    pub extra: usize,
+    pub missed: usize,
+}
+
+/// WPM in one of the layouts of a layoutfluid test.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct LayoutWpm {
+    pub layout: String,
+    pub wpm: f32,
+}
+
+/// A single test as it is stored in the history.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct TestResult {
+    /// Unix timestamp in milliseconds of the first keystroke.
+    pub timestamp: u64,
+    pub mode: ModeKind,
+    /// Seconds for time, word count for words and custom, and the [`QuoteLength`] for quotes.
+    ///
+    /// [`QuoteLength`]: crate::typing::QuoteLength
+    pub length: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quote_id: Option<usize>,
+    pub language: Language,
+    pub punctuation: bool,
+    pub numbers: bool,
+    #[serde(default)]
+    pub difficulty: Difficulty,
+    pub wpm: f32,
+    pub raw_wpm: f32,
+    pub accuracy: f32,
+    pub consistency: f32,
+    pub chars: CharCounts,
+    /// Duration in seconds.
+    pub duration: f64,
+    pub status: ResultStatus,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Names of the funboxes that were active.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub funbox: Vec<String>,
+    /// WPM per layout for layoutfluid tests.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub layouts: Vec<LayoutWpm>,
+}
+
+impl TestResult {
+    pub fn is_completed(&self) -> bool {
+        self.status == ResultStatus::Completed
+    }
+}
@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Tag {
+    pub name: String,
+    /// Active tags are attached to every new result.
+    pub active: bool,
+}
+
+#[derive(Default)]
+pub struct Tags {
+    path: PathBuf,
+    tags: Vec<Tag>,
+}
+
+impl Tags {
+    pub fn load(path: PathBuf) -> crate::Result<Self> {
+        let tags = match fs::read(&path) {
+            Ok(content) => serde_json::from_slice(&content)?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err.into()),
+        };
+
+        Ok(Self { path, tags })
+    }
+
+    fn save(&self) -> crate::Result<()> {
+        super::write_atomic(&self.path, &serde_json::to_vec_pretty(&self.tags)?)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Tag> {
+        self.tags.iter()
+    }
+
+    pub fn active(&self) -> Vec<String> {
+        self.tags
+            .iter()
+            .filter(|tag| tag.active)
+            .map(|tag| tag.name.clone())
+            .collect()
+    }
+
+    /// Creates a new, active tag. Does nothing if the tag already exists.
+    pub fn create(&mut self, name: &str) -> crate::Result<()> {
+        let name = name.trim();
+        if name.is_empty() || self.tags.iter().any(|tag| tag.name == name) {
+            return Ok(());
+        }
+
+        self.tags.push(Tag {
+            name: name.to_string(),
+            active: true,
+        });
+        self.save()
+    }
+
+    pub fn remove(&mut self, name: &str) -> crate::Result<()> {
+        self.tags.retain(|tag| tag.name != name);
+        self.save()
+    }
+
+    pub fn toggle(&mut self, name: &str) -> crate::Result<()> {
+        if let Some(tag) = self.tags.iter_mut().find(|tag| tag.name == name) {
+            tag.active = !tag.active;
+        }
+        self.save()
+    }
+}